@@ -0,0 +1,122 @@
+use crate::{days_calculator::get_weekday, model::get_timeoff_requests::timeoff_request::TimeOffRequest};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    Approve,
+    Deny,
+    Skip,
+}
+
+impl Decision {
+    /// Parses an answer to the interactive prompt, anything unknown skips the request
+    pub fn parse(answer: &str) -> Self {
+        match answer.trim().to_lowercase().as_str() {
+            "a" | "approve" => Decision::Approve,
+            "d" | "deny" => Decision::Deny,
+            _ => Decision::Skip,
+        }
+    }
+
+    /// Status sent to Bamboo, None when the request is left as it is
+    pub fn status(&self) -> Option<&'static str> {
+        match self {
+            Decision::Approve => Some("approved"),
+            Decision::Deny => Some("denied"),
+            Decision::Skip => None,
+        }
+    }
+
+    pub fn is_allowed(&self, request: &TimeOffRequest) -> bool {
+        let actions = request.actions.as_ref();
+        match self {
+            Decision::Approve => actions.is_some_and(|actions| actions.approve),
+            Decision::Deny => actions.is_some_and(|actions| actions.deny),
+            Decision::Skip => true,
+        }
+    }
+}
+
+/// Requests waiting for a decision which the API key is allowed to approve or deny
+pub fn pending_requests(requests: Vec<TimeOffRequest>) -> Vec<TimeOffRequest> {
+    requests
+        .into_iter()
+        .filter(|request| request.status.status == "requested")
+        .filter(|request| Decision::Approve.is_allowed(request) || Decision::Deny.is_allowed(request))
+        .collect()
+}
+
+pub fn describe(request: &TimeOffRequest) -> String {
+    let weekday = |day: &str| get_weekday(day).unwrap_or_default();
+    let mut description = format!(
+        "{}: {} {} {} from {} ({}) to {} ({})",
+        request.name,
+        request.r#type.name,
+        request.amount.amount,
+        request.amount.unit,
+        request.start,
+        weekday(&request.start),
+        request.end,
+        weekday(&request.end),
+    );
+    if let Some(note) = request.notes.as_ref().and_then(|notes| notes.employee.as_ref()) {
+        description.push_str(&format!(", note: {note}"));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(id: &str, status: &str, approve: bool, deny: bool) -> TimeOffRequest {
+        serde_json::from_value(json!({
+            "id": id,
+            "employeeId": "12",
+            "name": "Anna Nowak",
+            "status": { "lastChanged": "2024-08-01", "lastChangedByUserId": "1", "status": status },
+            "start": "2024-08-19",
+            "end": "2024-08-20",
+            "created": "2024-08-01",
+            "type": { "id": "78", "name": "Vacation", "icon": "palm-trees" },
+            "amount": { "unit": "days", "amount": "2" },
+            "actions": { "view": true, "edit": false, "cancel": false, "approve": approve, "deny": deny, "bypass": false },
+            "notes": { "employee": "Summer trip" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pending_requests() {
+        let requests = vec![
+            request("1", "requested", true, true),
+            request("2", "approved", true, true),
+            request("3", "requested", false, false),
+            request("4", "requested", false, true),
+        ];
+
+        let pending: Vec<String> = pending_requests(requests).into_iter().map(|request| request.id).collect();
+
+        assert_eq!(pending, vec!["1", "4"]);
+    }
+
+    #[test]
+    fn test_decision() {
+        let deny_only = request("4", "requested", false, true);
+
+        assert_eq!(Decision::parse(" A "), Decision::Approve);
+        assert_eq!(Decision::parse("deny"), Decision::Deny);
+        assert_eq!(Decision::parse(""), Decision::Skip);
+        assert!(!Decision::Approve.is_allowed(&deny_only));
+        assert!(Decision::Deny.is_allowed(&deny_only));
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&request("1", "requested", true, true)),
+            "Anna Nowak: Vacation 2 days from 2024-08-19 (Monday) to 2024-08-20 (Tuesday), note: Summer trip"
+        );
+    }
+}
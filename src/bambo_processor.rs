@@ -1,4 +1,36 @@
-use crate::{bamboo_client::BambooClient, model::{get_timeoff_requests::timeoff_request::TimeOffRequest, get_whos_out::time_off_entry::TimeOffEntry}};
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{approvals::{pending_requests, Decision}, bamboo_client::BambooClient, capacity::{calculate_capacity, SprintCapacity}, clock::{clock_status, ClockStatus}, days_calculator::{format_date, get_eligible_days_this_month, get_working_days, DateRange}, entry_editor::shift_entries, entry_planner::{busy_windows_from_entries, busy_windows_from_time_off, filled_days, plan_entries, plan_remote_changes, remove_existing, EntryPlan}, ics, model::{add_timeoff_request::add_request::AddTimeOffRequest, add_timesheet_entry::add_entry_request::AddTimesheetEntry, change_timeoff_status::change_status_request::ChangeStatusRequest, get_employee_directory::employee::Employee, get_timeoff_requests::timeoff_request::TimeOffRequest, get_timesheet_entries::timesheet_entry::TimsheetEntry, get_whos_out::time_off_entry::TimeOffEntry}, plan_file::{ApplyResult, PartlyApplied, PlanFile}, public_holidays, submission_lock::{lock_path, SubmissionLock}, timeoff_balance::{summarize_balances, PolicyBalance}, timeoff_request::{build_request, find_request_type}, work_schedule::WorkSchedule};
+
+/// Entries added by a submission and entries skipped because they were already in Bamboo
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct SubmitResult {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+/// Everything known about the employee's timesheet in a range of days
+pub struct TimesheetStatus {
+    pub bank_holidays: Vec<String>,
+    pub vacation_days: Vec<String>,
+    pub out_of_office_days: Vec<String>,
+    pub eligible_days: Vec<String>,
+    pub timesheet_entries: Vec<TimsheetEntry>,
+    pub entry_plan: EntryPlan,
+}
+
+/// Days missing in the timesheet of a single team member
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EmployeeReport {
+    pub employee_id: i32,
+    pub name: String,
+    pub missing_days: Vec<String>,
+}
 
 pub struct BambooProcessor<'a> {
     bamboo_client: &'a BambooClient<'a>
@@ -11,64 +43,558 @@ impl <'a> BambooProcessor<'a> {
         }
     }
 
-    pub async fn get_bank_holidays(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let whos_out = self.bamboo_client.get_whos_out().await;
-        let timeoff_entries: Vec<TimeOffEntry>;
-        match whos_out {
-            Ok(entries) => timeoff_entries = entries,
+    #[tracing::instrument(skip_all, fields(from = %range.start, to = %range.end))]
+    pub async fn get_timesheet_status(&self, range: &DateRange) -> Result<TimesheetStatus, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let working_days = get_working_days(range, &config.schedule);
+        let bank_holidays = self.get_bank_holidays(range).await?;
+        // managers can see requests of their reports as well
+        let vacation_requests: Vec<TimeOffRequest> = self.get_timeoff_requests(range).await?
+            .into_iter()
+            .filter(|request| request.employee_id == config.employee_id.to_string())
+            .collect();
+        let vacation_days = get_vacation_days(&vacation_requests);
+        let out_of_office_days = match &config.ics_path {
+            Some(ics_path) => ics::get_out_of_office_days(ics_path, range.start, range.end)?,
+            None => Vec::new(),
+        };
+        let timesheet_entries = self.get_timesheet_entries(range).await?;
+        // partially filled days stay eligible, new entries are planned around the existing ones
+        let already_added_days = filled_days(&timesheet_entries, config.schedule.daily_hours());
+
+        let eligible_days = get_eligible_days_this_month(
+            &working_days,
+            &[vacation_days.clone(), out_of_office_days.clone()].concat(),
+            &already_added_days,
+            &bank_holidays,
+        );
+
+        let mut busy_windows = busy_windows_from_entries(&timesheet_entries);
+        busy_windows.extend(busy_windows_from_time_off(&get_partial_time_off(&vacation_requests, config.schedule.daily_hours()), &config.schedule));
+        let entry_plan = plan_entries(config.employee_id, &eligible_days, &busy_windows, &config.schedule);
+
+        Ok(TimesheetStatus {
+            bank_holidays,
+            vacation_days,
+            out_of_office_days,
+            eligible_days,
+            timesheet_entries,
+            entry_plan,
+        })
+    }
+
+    /// Plan of entries to create, update and delete in the range, to be reviewed and applied later
+    pub async fn create_plan(&self, range: &DateRange) -> Result<PlanFile, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let status = self.get_timesheet_status(range).await?;
+        let now = Local::now();
+        let days_off = [status.bank_holidays, status.vacation_days].concat();
+        let changes = plan_remote_changes(&status.timesheet_entries, &days_off, &format_date(&now.date_naive()), &config.schedule);
+        let created_at = now.format("%Y-%m-%dT%H:%M:%S").to_string();
+        Ok(PlanFile::new(config.employee_id, range, &created_at, status.entry_plan.entries, changes, &status.timesheet_entries))
+    }
+
+    /// Deletes, updates and creates entries of the plan, refuses to run when the remote timesheet changed since planning
+    #[tracing::instrument(skip_all, fields(from = %plan.from, to = %plan.to))]
+    pub async fn apply_plan(&self, plan: &PlanFile) -> Result<ApplyResult, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        if plan.employee_id != config.employee_id {
+            return Err(format!("Plan was made for employee {}, not {}", plan.employee_id, config.employee_id).into());
+        }
+        plan.check_entries()?;
+        // the remote state must not change between the check and the last posted entry
+        let _lock = SubmissionLock::acquire(&lock_path(config)).await?;
+        let remote = self.get_timesheet_entries(&plan.range()?).await?;
+        plan.check_remote(&remote)?;
+
+        let mut result = ApplyResult::default();
+        match self.apply_changes(plan, &mut result).await {
+            Ok(()) => Ok(result),
+            Err(e) if result == ApplyResult::default() => Err(e),
+            Err(e) => {
+                tracing::error!(deleted = result.deleted, updated = result.updated, created = result.created, error = %e, "Plan partly applied");
+                Err(Box::new(PartlyApplied { applied: result, error: e.to_string() }))
+            }
+        }
+    }
+
+    /// Counts every step in `result` as soon as it is done, so a failure can tell what was already changed
+    async fn apply_changes(&self, plan: &PlanFile, result: &mut ApplyResult) -> Result<(), Box<dyn std::error::Error>> {
+        if !plan.delete.is_empty() {
+            self.bamboo_client.delete_time_entries(&plan.delete).await?;
+            result.deleted = plan.delete.len();
+        }
+        if !plan.update.is_empty() {
+            let response = self.bamboo_client.add_time_entries(&plan.update).await?;
+            if !response.status().is_success() {
+                return Err(format!("Error updating entries, status {}", response.status()).into());
+            }
+            result.updated = plan.update.len();
+        }
+        result.created = self.submit_locked(&plan.create).await?.added;
+        Ok(())
+    }
+
+    /// Missing days of every employee, using the configured schedule, holidays and each employee's own time off
+    #[tracing::instrument(skip_all, fields(from = %range.start, to = %range.end, employees = employee_ids.len()))]
+    pub async fn get_team_report(&self, range: &DateRange, employee_ids: &[i32]) -> Result<Vec<EmployeeReport>, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let working_days = get_working_days(range, &config.schedule);
+        let bank_holidays = self.get_bank_holidays(range).await?;
+        let vacation_requests = self.get_timeoff_requests(range).await?;
+        let mut entries_by_employee = match self.bamboo_client.get_team_timesheet_entries(range, employee_ids).await {
+            Ok(entries) => entries.into_iter().into_group_map_by(|entry| entry.employee_id),
             Err(e) => {
-                println!("Error while getting who's out: {:?}", e);
+                tracing::error!(error = %e, "Error while getting timesheet entries of the team");
                 return Err(e);
             }
+        };
+        // names are nice to have, directory may be disabled for the company
+        let directory = self.bamboo_client.get_employee_directory().await.unwrap_or_default();
+
+        let mut reports = Vec::new();
+        for employee_id in employee_ids {
+            let employee_requests: Vec<&TimeOffRequest> = vacation_requests
+                .iter()
+                .filter(|request| request.employee_id == employee_id.to_string())
+                .collect();
+            let vacation_days = get_vacation_days(employee_requests.iter().copied());
+            let employee_entries = entries_by_employee.remove(employee_id).unwrap_or_default();
+            let already_added_days = filled_days(&employee_entries, config.schedule.daily_hours());
+            let name = get_employee_name(*employee_id, &directory, &employee_requests);
+
+            reports.push(EmployeeReport {
+                employee_id: *employee_id,
+                name,
+                missing_days: get_eligible_days_this_month(&working_days, &vacation_days, &already_added_days, &bank_holidays),
+            });
         }
+        Ok(reports)
+    }
+
+    /// Available hours of every employee in the range. Employees missing in `part_time` work according to the configured schedule.
+    pub async fn get_sprint_capacity(&self, range: &DateRange, employee_ids: &[i32], part_time: &HashMap<i32, WorkSchedule>) -> Result<SprintCapacity, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let bank_holidays = self.get_bank_holidays(range).await?;
+        let vacation_requests = self.get_timeoff_requests(range).await?;
+        let directory = self.bamboo_client.get_employee_directory().await.unwrap_or_default();
+
+        let employees = employee_ids
+            .iter()
+            .map(|employee_id| {
+                let employee_requests: Vec<&TimeOffRequest> = vacation_requests
+                    .iter()
+                    .filter(|request| request.employee_id == employee_id.to_string())
+                    .collect();
+                calculate_capacity(
+                    range,
+                    *employee_id,
+                    &get_employee_name(*employee_id, &directory, &employee_requests),
+                    part_time.get(employee_id).unwrap_or(&config.schedule),
+                    &bank_holidays,
+                    &employee_requests,
+                )
+            })
+            .collect();
+        Ok(SprintCapacity::new(range, employees))
+    }
+
+    /// Balances per policy today and at the end of the year with time off already approved this year
+    pub async fn get_timeoff_balances(&self) -> Result<Vec<PolicyBalance>, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let today = Local::now().date_naive();
+        let year = DateRange::new(
+            NaiveDate::from_ymd_opt(today.year(), 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap(),
+        )?;
+        let current = self.bamboo_client.get_timeoff_balances(&today).await?;
+        let projected = self.bamboo_client.get_timeoff_balances(&year.end).await?;
+        let own_requests: Vec<TimeOffRequest> = self
+            .get_timeoff_requests(&year)
+            .await?
+            .into_iter()
+            .filter(|request| request.employee_id == config.employee_id.to_string())
+            .collect();
+
+        Ok(summarize_balances(&current, &projected, &own_requests, config.schedule.daily_hours()))
+    }
+
+    /// Request for the working days in the range without bank holidays, type is validated against Bamboo time off types
+    pub async fn build_timeoff_request(&self, range: &DateRange, time_off_type: &str, day_amounts: &[(String, f32)], note: Option<&str>) -> Result<AddTimeOffRequest, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let types = self.bamboo_client.get_timeoff_types().await?;
+        let request_type = find_request_type(&types, time_off_type)?;
+        let bank_holidays = self.get_bank_holidays(range).await?;
+        let days: Vec<String> = get_working_days(range, &config.schedule)
+            .into_iter()
+            .filter(|day| !bank_holidays.contains(day))
+            .collect();
+
+        Ok(build_request(request_type, &days, day_amounts, note, config.schedule.daily_hours())?)
+    }
+
+    /// Requests in the range waiting for approval which the API key may approve or deny
+    pub async fn get_pending_approvals(&self, range: &DateRange) -> Result<Vec<TimeOffRequest>, Box<dyn std::error::Error>> {
+        Ok(pending_requests(self.get_timeoff_requests(range).await?))
+    }
+
+    pub async fn decide_timeoff_request(&self, request: &TimeOffRequest, decision: Decision, note: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(status) = decision.status() else {
+            return Ok(());
+        };
+        if !decision.is_allowed(request) {
+            return Err(format!("Not allowed to change status of request {} to {status}", request.id).into());
+        }
+        let change = ChangeStatusRequest { status: status.to_string(), note: note.map(String::from) };
+        self.bamboo_client.change_timeoff_status(&request.id, &change).await
+    }
+
+    pub async fn get_clock_status(&self) -> Result<ClockStatus, Box<dyn std::error::Error>> {
+        let now = Local::now().naive_local();
+        let today = DateRange::new(now.date(), now.date())?;
+        let entries = self.get_timesheet_entries(&today).await?;
+        Ok(clock_status(&entries, &format_date(&now.date()), now.time()))
+    }
+
+    /// Clock entries of the configured employee, hour entries are left out
+    pub async fn get_clock_entries(&self, range: &DateRange) -> Result<Vec<TimsheetEntry>, Box<dyn std::error::Error>> {
+        let entries = self.get_timesheet_entries(range).await?;
+        Ok(entries.into_iter().filter(|entry| entry.r#type == "clock").collect())
+    }
+
+    /// Moves all clock entries of the day by `shift`, returns number of updated entries
+    pub async fn shift_day(&self, day: &NaiveDate, shift: Duration) -> Result<usize, Box<dyn std::error::Error>> {
+        let entries = self.get_clock_entries(&DateRange::new(*day, *day)?).await?;
+        let updates = shift_entries(&entries, &format_date(day), shift)?;
+        if updates.is_empty() {
+            return Ok(0);
+        }
+        let response = self.bamboo_client.add_time_entries(&updates).await?;
+        if !response.status().is_success() {
+            return Err(format!("Error updating entries, status {}", response.status()).into());
+        }
+        Ok(updates.len())
+    }
+
+    /// Employees whose supervisor in the directory is the configured employee
+    pub async fn get_direct_reports(&self) -> Result<Vec<i32>, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let directory = match self.bamboo_client.get_employee_directory().await {
+            Ok(directory) => directory,
+            Err(e) => {
+                tracing::error!(error = %e, "Error while getting employee directory");
+                return Err(e);
+            }
+        };
+        let manager = directory
+            .iter()
+            .find(|employee| employee.id == config.employee_id.to_string())
+            .ok_or(format!("Employee {} not found in the directory", config.employee_id))?;
+
+        Ok(directory
+            .iter()
+            .filter(|employee| employee.supervisor.as_ref() == Some(&manager.display_name))
+            .filter_map(|employee| employee.id.parse::<i32>().ok())
+            .collect())
+    }
+
+    /// Everybody's time off and holidays. People can be narrowed down to a department from the directory and/or employee ids.
+    pub async fn get_whos_out(&self, range: &DateRange, department: Option<&str>, employee_ids: &[i32]) -> Result<Vec<TimeOffEntry>, Box<dyn std::error::Error>> {
+        let mut entries = match self.bamboo_client.get_whos_out(range).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::error!(error = %e, "Error while getting who's out");
+                return Err(e);
+            }
+        };
+
+        if let Some(department) = department {
+            let directory = match self.bamboo_client.get_employee_directory().await {
+                Ok(directory) => directory,
+                Err(e) => {
+                    tracing::error!(error = %e, "Error while getting employee directory");
+                    return Err(e);
+                }
+            };
+            let department_ids: Vec<String> = directory
+                .into_iter()
+                .filter(|employee| employee.department.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(department)))
+                .map(|employee| employee.id)
+                .collect();
+            entries.retain(|entry| entry.employee_id.is_none_or(|id| department_ids.contains(&id.to_string())));
+        }
+        if !employee_ids.is_empty() {
+            entries.retain(|entry| entry.employee_id.is_none_or(|id| employee_ids.contains(&id)));
+        }
+        Ok(entries)
+    }
+
+    /// Holidays from Bamboo merged with the configured built-in calendar and holidays file
+    pub async fn get_bank_holidays(&self, range: &DateRange) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let config = self.bamboo_client.config;
+        let whos_out = self.bamboo_client.get_whos_out(range).await;
+        let timeoff_entries: Vec<TimeOffEntry> = match whos_out {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::error!(error = %e, "Error while getting who's out");
+                return Err(e);
+            }
+        };
         let mut bank_holidays = Vec::new();
-    
+
         for entry in timeoff_entries {
-            if entry.r#type == "holiday".to_string() {
+            if entry.r#type == "holiday" {
                 if entry.start == entry.end {
                     bank_holidays.push(String::from(&entry.start));
                 } else {
-                    println!("There is bank holiday from {} to {}, it is not handled automatically!", entry.start, entry.end);
+                    tracing::warn!(start = %entry.start, end = %entry.end, "Bank holiday spanning several days is not handled automatically");
                 }
             }
         }
-    
-        Ok(bank_holidays)
+
+        if let Some(country) = &config.holiday_country {
+            let public_holidays = public_holidays::get_public_holidays(country, config.holiday_region.as_deref(), range.start, range.end)?;
+            bank_holidays.extend(public_holidays.iter().map(|holiday| format_date(&holiday.date)));
+        }
+        if let Some(holidays_file) = &config.holidays_file {
+            let file_holidays = public_holidays::load_holidays_file(holidays_file, range.start, range.end)?;
+            bank_holidays.extend(file_holidays.iter().map(|holiday| format_date(&holiday.date)));
+        }
+
+        Ok(bank_holidays.into_iter().unique().collect())
     }
-    
-    pub async fn get_vacation_days(&self) ->Result<Vec<String>, Box<dyn std::error::Error>> {
-        let response = self.bamboo_client.get_timeoff_requests().await;
-        let vacation_requests: Vec<TimeOffRequest>;
-        match response {
-            Ok(entries) => vacation_requests = entries,
-            Err(e) => {
-                println!("Error while getting vacation days: {:?}", e);
-                return Err(e);
-            }
+
+    /// Adds entries in batch, falls back to day by day when the batch is rejected. Entries already in Bamboo are skipped.
+    #[tracing::instrument(skip_all, fields(entries = entries.len()))]
+    pub async fn submit_entries(&self, entries: &[AddTimesheetEntry]) -> Result<SubmitResult, Box<dyn std::error::Error>> {
+        if entries.is_empty() {
+            return Ok(SubmitResult::default());
+        }
+        let _lock = SubmissionLock::acquire(&lock_path(self.bamboo_client.config)).await?;
+        self.submit_locked(entries).await
+    }
+
+    /// `submit_entries` for callers already holding the lock file
+    async fn submit_locked(&self, entries: &[AddTimesheetEntry]) -> Result<SubmitResult, Box<dyn std::error::Error>> {
+        if entries.is_empty() {
+            return Ok(SubmitResult::default());
+        }
+        match self.post_new_entries(entries).await {
+            Ok(result) => return Ok(result),
+            Err(e) => tracing::warn!(error = %e, "Error adding entries in batch, trying day by day"),
         }
-        let mut vacation_days = Vec::new();
-    
-        for request in vacation_requests {
-            
-            for vacation_day in request.dates.unwrap_or_default() {
-                if vacation_day.1 == "1".to_string() {
-                    vacation_days.push(vacation_day.0);
+
+        let mut result = SubmitResult::default();
+        let days: Vec<(String, Vec<AddTimesheetEntry>)> = entries
+            .iter()
+            .chunk_by(|entry| entry.date.clone())
+            .into_iter()
+            .map(|(day, day_entries)| (day, day_entries.cloned().collect()))
+            .collect();
+        for (day, day_entries) in days {
+            match self.post_new_entries(&day_entries).await {
+                Ok(day_result) => {
+                    result.added += day_result.added;
+                    result.skipped += day_result.skipped;
                 }
+                Err(e) => tracing::error!(%day, error = %e, "Error adding entries"),
             }
         }
-    
-        Ok(vacation_days)
+        Ok(result)
+    }
+
+    /// Posts entries which are not in Bamboo yet in a single request. Remote entries are fetched again
+    /// right before posting while holding the lock file, so crashed or concurrent runs never add duplicates.
+    #[tracing::instrument(skip_all, fields(entries = entries.len()))]
+    pub async fn add_new_entries(&self, entries: &[AddTimesheetEntry]) -> Result<SubmitResult, Box<dyn std::error::Error>> {
+        let _lock = SubmissionLock::acquire(&lock_path(self.bamboo_client.config)).await?;
+        self.post_new_entries(entries).await
     }
 
-    pub async fn get_already_added_days(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let timesheet_entries = self.bamboo_client.get_timesheet_entries().await;
+    async fn post_new_entries(&self, entries: &[AddTimesheetEntry]) -> Result<SubmitResult, Box<dyn std::error::Error>> {
+        let (Some(first), Some(last)) = (entries.iter().map(|entry| &entry.date).min(), entries.iter().map(|entry| &entry.date).max()) else {
+            return Ok(SubmitResult::default());
+        };
+        let remote = self.get_timesheet_entries(&DateRange::parse(Some(first), Some(last))?).await?;
+        let new_entries = remove_existing(entries, &remote);
+        let skipped = entries.len() - new_entries.len();
+        if skipped > 0 {
+            tracing::info!(skipped, "Skipping entries already in Bamboo");
+        }
+        if new_entries.is_empty() {
+            return Ok(SubmitResult { added: 0, skipped });
+        }
 
-        match timesheet_entries {
-            Ok(entries) => Ok(entries.into_iter().map(|entry| entry.date).collect()),
+        let response = self.bamboo_client.add_time_entries(&new_entries).await?;
+        if response.status() != 201 {
+            return Err(format!("Error adding entries, status {}", response.status()).into());
+        }
+        Ok(SubmitResult { added: new_entries.len(), skipped })
+    }
+
+    async fn get_timeoff_requests(&self, range: &DateRange) -> Result<Vec<TimeOffRequest>, Box<dyn std::error::Error>> {
+        match self.bamboo_client.get_timeoff_requests(range).await {
+            Ok(entries) => Ok(entries),
             Err(e) => {
-                println!("Error while getting already added days: {:?}", e);
+                tracing::error!(error = %e, "Error while getting vacation days");
                 Err(e)
             }
         }
     }
-}
\ No newline at end of file
+
+    async fn get_timesheet_entries(&self, range: &DateRange) -> Result<Vec<TimsheetEntry>, Box<dyn std::error::Error>> {
+        match self.bamboo_client.get_timesheet_entries(range).await {
+            Ok(entries) => Ok(entries),
+            Err(e) => {
+                tracing::error!(error = %e, "Error while getting already added days");
+                Err(e)
+            }
+        }
+    }
+}
+
+fn get_employee_name(employee_id: i32, directory: &[Employee], requests: &[&TimeOffRequest]) -> String {
+    directory
+        .iter()
+        .find(|employee| employee.id == employee_id.to_string())
+        .map(|employee| String::from(&employee.display_name))
+        .or(requests.first().map(|request| String::from(&request.name)))
+        .unwrap_or(format!("Employee {employee_id}"))
+}
+
+fn get_vacation_days<'r>(vacation_requests: impl IntoIterator<Item = &'r TimeOffRequest>) -> Vec<String> {
+    let mut vacation_days = Vec::new();
+
+    for request in vacation_requests {
+        for vacation_day in request.dates.iter().flatten() {
+            if vacation_day.1 == "1" {
+                vacation_days.push(String::from(vacation_day.0));
+            }
+        }
+    }
+
+    vacation_days
+}
+
+/// Days with less than a full day of time off, with the amount converted to hours
+fn get_partial_time_off(vacation_requests: &[TimeOffRequest], daily_hours: f32) -> Vec<(String, f32)> {
+    let mut partial_days = Vec::new();
+
+    for request in vacation_requests {
+        let hours_per_unit = if request.amount.unit == "hours" { 1.0 } else { daily_hours };
+        for (day, amount) in request.dates.iter().flatten() {
+            // full days are already excluded as vacation days
+            if amount == "1" {
+                continue;
+            }
+            if let Ok(amount) = amount.parse::<f32>() {
+                partial_days.push((String::from(day), amount * hours_per_unit));
+            }
+        }
+    }
+
+    partial_days
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::{matchers::path_regex, Mock, MockServer, ResponseTemplate};
+
+    use crate::config::ConfigBuilder;
+
+    use super::*;
+
+    async fn mock_bamboo(timesheet_entries: serde_json::Value) -> MockServer {
+        let bamboo = MockServer::start().await;
+        Mock::given(path_regex("/timesheet_entries$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(timesheet_entries))
+            .mount(&bamboo)
+            .await;
+        Mock::given(path_regex("/time_off/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+            .mount(&bamboo)
+            .await;
+        bamboo
+    }
+
+    #[tokio::test]
+    async fn test_status_plans_around_existing_entries() {
+        let bamboo = mock_bamboo(json!([
+            { "id": 1, "employeeId": 12, "type": "clock", "date": "2024-08-13", "start": "10:00", "end": "11:00", "timezone": "UTC", "hours": 1 },
+            { "id": 2, "employeeId": 12, "type": "clock", "date": "2024-08-14", "start": "08:00", "end": "16:00", "timezone": "UTC", "hours": 8 },
+        ]))
+        .await;
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .build()
+            .unwrap();
+        let bamboo_client = BambooClient::new(&config);
+        let range = DateRange::parse(Some("2024-08-13"), Some("2024-08-14")).unwrap();
+
+        let status = BambooProcessor::new(&bamboo_client).get_timesheet_status(&range).await.unwrap();
+
+        assert_eq!(status.eligible_days, vec!["2024-08-13".to_string()]);
+        let planned: Vec<(&str, &str)> = status.entry_plan.entries.iter().map(|entry| (entry.start.as_str(), entry.end.as_str())).collect();
+        assert_eq!(planned, vec![("08:00", "10:00"), ("11:00", "16:00")]);
+        assert_eq!(status.entry_plan.conflicts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_team_report_keeps_partially_filled_days_missing() {
+        let bamboo = mock_bamboo(json!([
+            { "id": 1, "employeeId": 12, "type": "clock", "date": "2024-08-13", "start": "10:00", "end": "11:00", "timezone": "UTC", "hours": 1 },
+            { "id": 2, "employeeId": 12, "type": "clock", "date": "2024-08-14", "start": "08:00", "end": "16:00", "timezone": "UTC", "hours": 8 },
+            { "id": 3, "employeeId": 13, "type": "clock", "date": "2024-08-13", "start": "08:00", "end": "16:00", "timezone": "UTC", "hours": 8 },
+        ]))
+        .await;
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .build()
+            .unwrap();
+        let bamboo_client = BambooClient::new(&config);
+        let range = DateRange::parse(Some("2024-08-13"), Some("2024-08-14")).unwrap();
+
+        let reports = BambooProcessor::new(&bamboo_client).get_team_report(&range, &[12, 13]).await.unwrap();
+
+        assert_eq!(reports[0].missing_days, vec!["2024-08-13".to_string()]);
+        assert_eq!(reports[1].missing_days, vec!["2024-08-14".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_apply_plan_reports_partly_applied_changes() {
+        let entries = json!([
+            { "id": 1, "employeeId": 12, "type": "clock", "date": "2024-08-15", "start": "08:00", "end": "16:00", "timezone": "UTC", "hours": 8 },
+            { "id": 2, "employeeId": 12, "type": "clock", "date": "2024-08-13", "start": "08:00", "timezone": "UTC", "hours": 0 },
+        ]);
+        let bamboo = mock_bamboo(entries.clone()).await;
+        Mock::given(path_regex("/clock_entries/delete$")).respond_with(ResponseTemplate::new(200)).mount(&bamboo).await;
+        Mock::given(path_regex("/clock_entries/store$")).respond_with(ResponseTemplate::new(500)).mount(&bamboo).await;
+        let lock_file = std::env::temp_dir().join(format!("bamboo-bot-apply-{}.lock", std::process::id()));
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .lock_file(Some(lock_file.to_string_lossy().to_string()))
+            .build()
+            .unwrap();
+        let bamboo_client = BambooClient::new(&config);
+        let range = DateRange::parse(Some("2024-08-13"), Some("2024-08-15")).unwrap();
+        let remote: Vec<TimsheetEntry> = serde_json::from_value(entries).unwrap();
+        let changes = plan_remote_changes(&remote, &["2024-08-15".to_string()], "2024-08-20", &config.schedule);
+        let plan = PlanFile::new(12, &range, "2024-08-20T17:00:00", Vec::new(), changes, &remote);
+
+        let error = BambooProcessor::new(&bamboo_client).apply_plan(&plan).await.unwrap_err();
+
+        let partly_applied = error.downcast_ref::<PartlyApplied>().unwrap();
+        assert_eq!(partly_applied.applied, ApplyResult { created: 0, updated: 0, deleted: 1 });
+        assert!(error.to_string().contains("Run plan again"));
+    }
+}
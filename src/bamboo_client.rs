@@ -1,37 +1,68 @@
-use reqwest::Url;
-use serde::de::DeserializeOwned;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+use chrono::NaiveDate;
+use reqwest::{RequestBuilder, Response, Url};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::Instrument;
 
 use crate::{
-    config::Config, days_calculator::{get_first_day_of_the_month, get_today}, model::{
+    config::Config, days_calculator::{format_date, DateRange}, fixtures::{anonymize, fixture_name, Fixture, FixtureMode}, model::{
+        add_timeoff_request::add_request::{AddTimeOffRequest, AddTimeOffResponse},
         add_timesheet_entry::add_entry_request::{AddEntryRequest, AddTimesheetEntry},
-        get_timeoff_requests::timeoff_request::TimeOffRequest,
+        change_timeoff_status::change_status_request::ChangeStatusRequest,
+        clock_in::clock_in_request::ClockInRequest,
+        delete_clock_entries::delete_entries_request::DeleteEntriesRequest,
+        get_employee_directory::{directory::Directory, employee::Employee},
+        get_timeoff_balances::timeoff_balance::TimeOffBalance,
+        get_timeoff_requests::{request_type::RequestType, timeoff_request::TimeOffRequest},
+        get_timeoff_types::timeoff_types::TimeOffTypes,
         get_timesheet_entries::timesheet_entry::TimsheetEntry,
         get_whos_out::time_off_entry::TimeOffEntry,
     }
 };
 
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
 pub struct BambooClient<'a> {
     pub config: &'a Config,
     client: reqwest::Client,
+    fixtures: Option<FixtureMode>,
 }
 
 impl<'a> BambooClient<'a> {
     pub fn new(config: &'a Config) -> Self {
+        let fixtures = match (&config.record_fixtures, &config.replay_fixtures) {
+            (Some(dir), _) => Some(FixtureMode::record(dir)),
+            (None, Some(dir)) => Some(FixtureMode::replay(dir)),
+            (None, None) => None,
+        };
         BambooClient {
             config,
             client: reqwest::Client::new(),
+            fixtures,
         }
     }
 
     pub async fn get_timesheet_entries(
         &self,
+        range: &DateRange,
+    ) -> Result<Vec<TimsheetEntry>, Box<dyn std::error::Error>> {
+        self.get_team_timesheet_entries(range, &[self.config.employee_id]).await
+    }
+
+    pub async fn get_team_timesheet_entries(
+        &self,
+        range: &DateRange,
+        employee_ids: &[i32],
     ) -> Result<Vec<TimsheetEntry>, Box<dyn std::error::Error>> {
-        let start_of_this_month = get_first_day_of_the_month().format("%Y-%m-%d").to_string();
-        let today = get_today().format("%Y-%m-%d").to_string();
+        let employee_ids: Vec<String> = employee_ids.iter().map(|id| id.to_string()).collect();
         let params = [
-            ("start", start_of_this_month),
-            ("end", today),
-            ("employeeIds", self.config.employee_id.to_string()),
+            ("start", format_date(&range.start)),
+            ("end", format_date(&range.end)),
+            ("employeeIds", employee_ids.join(",")),
         ];
         let url = format!(
             "{}/api/gateway.php/{}/v1/time_tracking/timesheet_entries",
@@ -42,10 +73,7 @@ impl<'a> BambooClient<'a> {
         match url {
             Ok(url) => self.get_request(url).await.map(|r| r.unwrap_or_default()),
             Err(e) => {
-                println!(
-                    "Error while parsing url {:?} with params {:?}: {:?}",
-                    &url, &params, &e
-                );
+                tracing::error!(error = %e, "Error while parsing url");
                 Err(Box::new(e))
             }
         }
@@ -53,7 +81,7 @@ impl<'a> BambooClient<'a> {
 
     pub async fn add_time_entries(
         &self,
-        dates: &Vec<String>,
+        entries: &[AddTimesheetEntry],
     ) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
         let url = format!(
             "{}/api/gateway.php/{}/v1/time_tracking/clock_entries/store",
@@ -61,71 +89,49 @@ impl<'a> BambooClient<'a> {
         );
 
         let body = AddEntryRequest {
-            entries: dates
-                .into_iter()
-                .map(|date| AddTimesheetEntry {
-                    employee_id: self.config.employee_id,
-                    date: String::from(date),
-                    start: "08:00".to_string(),
-                    end: "16:00".to_string(),
-                })
-                .collect(),
+            entries: entries.to_vec(),
         };
 
-        let response = self
-            .client
-            .post(url)
-            .header(String::from("accept"), String::from("application/json"))
-            .json(&body)
-            .basic_auth(&self.config.api_key, Option::<String>::None)
-            .send()
-            .await?;
+        let request = self.client.post(url).json(&body);
+        let response = self.send(request).await?;
 
         Ok(response)
     }
 
-    pub async fn add_time_entry(
-        &self,
-        date: &str,
-    ) -> Result<reqwest::Response, Box<dyn std::error::Error>> {
+    pub async fn delete_time_entries(&self, entry_ids: &[u32]) -> Result<(), Box<dyn std::error::Error>> {
         let url = format!(
-            "{}/api/gateway.php/{}/v1/time_tracking/clock_entries/store",
+            "{}/api/gateway.php/{}/v1/time_tracking/clock_entries/delete",
             self.config.bamboo_base_url, self.config.company
         );
-        let body = AddEntryRequest {
-            entries: vec![AddTimesheetEntry {
-                employee_id: self.config.employee_id,
-                date: String::from(date),
-                start: "08:00".to_string(),
-                end: "16:00".to_string(),
-            }],
+
+        let body = DeleteEntriesRequest {
+            clock_entry_ids: entry_ids.to_vec(),
         };
 
-        let response = self
-            .client
-            .post(url)
-            .header(String::from("accept"), String::from("application/json"))
-            .json(&body)
-            .basic_auth(&self.config.api_key, Option::<String>::None)
-            .send()
-            .await?;
+        let request = self.client.post(url).json(&body);
+        let response = self.send(request).await?;
 
-        Ok(response)
+        if !response.status().is_success() {
+            return Err(format!(
+                "Deleting entries failed with status {:?}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )
+            .into());
+        }
+        Ok(())
     }
 
     pub async fn get_timeoff_requests(
         &self,
+        range: &DateRange,
     ) -> Result<Vec<TimeOffRequest>, Box<dyn std::error::Error>> {
-        // let two_months_ago = get_first_day_of_the_month() - Months::new(2);
-        let start_date = get_first_day_of_the_month().format("%Y-%m-%d").to_string();
-        let today = get_today().format("%Y-%m-%d").to_string();
-
         // let params = [
         //     ("start", start_date),
         //     ("end", today),
         //     ("employeeId", self.config.employee_id.to_string()),
         // ];
-        let params = [("start", start_date), ("end", today)];
+        let params = [("start", format_date(&range.start)), ("end", format_date(&range.end))];
 
         let url = format!(
             "{}/api/gateway.php/{}/v1/time_off/requests",
@@ -137,19 +143,17 @@ impl<'a> BambooClient<'a> {
         match url {
             Ok(url) => self.get_request(url).await.map(|r| r.unwrap_or_default()),
             Err(e) => {
-                println!(
-                    "Error while parsing url {:?} with params {:?}: {:?}",
-                    &url, &params, &e
-                );
+                tracing::error!(error = %e, "Error while parsing url");
                 Err(Box::new(e))
             }
         }
     }
 
-    pub async fn get_whos_out(&self) -> Result<Vec<TimeOffEntry>, Box<dyn std::error::Error>> {
-        let start_of_this_month = get_first_day_of_the_month().format("%Y-%m-%d").to_string();
-        let today = get_today().format("%Y-%m-%d").to_string();
-        let params = [("start", start_of_this_month), ("end", today)];
+    pub async fn get_whos_out(
+        &self,
+        range: &DateRange,
+    ) -> Result<Vec<TimeOffEntry>, Box<dyn std::error::Error>> {
+        let params = [("start", format_date(&range.start)), ("end", format_date(&range.end))];
         let url = format!(
             "{}/api/gateway.php/{}/v1/time_off/whos_out",
             self.config.bamboo_base_url, self.config.company
@@ -160,34 +164,158 @@ impl<'a> BambooClient<'a> {
         match url {
             Ok(url) => self.get_request(url).await.map(|r| r.unwrap_or_default()),
             Err(e) => {
-                println!(
-                    "Error while parsing url {:?} with params {:?}: {:?}",
-                    &url, &params, &e
-                );
+                tracing::error!(error = %e, "Error while parsing url");
                 Err(Box::new(e))
             }
         }
     }
 
-    async fn get_request<T: DeserializeOwned>(
+    /// Balances of every time off policy estimated for the given day
+    pub async fn get_timeoff_balances(
         &self,
-        url: Url,
-    ) -> Result<Option<T>, Box<dyn std::error::Error>> {
-        let response_result = self
-            .client
-            .get(url)
-            .header("Accept", "application/json")
-            .basic_auth(&self.config.api_key, Option::<String>::None)
-            .send()
-            .await;
+        end: &NaiveDate,
+    ) -> Result<Vec<TimeOffBalance>, Box<dyn std::error::Error>> {
+        let params = [("end", format_date(end))];
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/employees/{}/time_off/calculator",
+            self.config.bamboo_base_url, self.config.company, self.config.employee_id
+        );
+
+        let url = reqwest::Url::parse_with_params(&url, &params);
 
-        let response = match response_result {
-            Ok(resp) => resp,
+        match url {
+            Ok(url) => self.get_request(url).await.map(|r| r.unwrap_or_default()),
             Err(e) => {
-                println!("Error while sending get request: {:?}", e);
-                return Err(Box::new(e));
+                tracing::error!(error = %e, "Error while parsing url");
+                Err(Box::new(e))
             }
-        };
+        }
+    }
+
+    pub async fn get_employee_directory(&self) -> Result<Vec<Employee>, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/employees/directory",
+            self.config.bamboo_base_url, self.config.company
+        );
+
+        match reqwest::Url::parse(&url) {
+            Ok(url) => self
+                .get_request::<Directory>(url)
+                .await
+                .map(|r| r.map(|directory| directory.employees).unwrap_or_default()),
+            Err(e) => {
+                tracing::error!(error = %e, "Error while parsing url");
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    pub async fn get_timeoff_types(&self) -> Result<Vec<RequestType>, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/meta/time_off/types",
+            self.config.bamboo_base_url, self.config.company
+        );
+
+        match reqwest::Url::parse(&url) {
+            Ok(url) => self
+                .get_request::<TimeOffTypes>(url)
+                .await
+                .map(|r| r.map(|types| types.time_off_types).unwrap_or_default()),
+            Err(e) => {
+                tracing::error!(error = %e, "Error while parsing url");
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    pub async fn add_timeoff_request(
+        &self,
+        request: &AddTimeOffRequest,
+    ) -> Result<AddTimeOffResponse, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/employees/{}/time_off/request",
+            self.config.bamboo_base_url, self.config.company, self.config.employee_id
+        );
+
+        let request = self.client.put(url).json(request);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Time off request failed with status {:?}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )
+            .into());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn change_timeoff_status(
+        &self,
+        request_id: &str,
+        change: &ChangeStatusRequest,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/time_off/requests/{}/status",
+            self.config.bamboo_base_url, self.config.company, request_id
+        );
+
+        let request = self.client.put(url).json(change);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Changing status of request {} failed with status {:?}: {}",
+                request_id,
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    pub async fn clock_in(&self, request: &ClockInRequest) -> Result<TimsheetEntry, Box<dyn std::error::Error>> {
+        self.post_clock_action("clock_in", request).await
+    }
+
+    pub async fn clock_out(&self) -> Result<TimsheetEntry, Box<dyn std::error::Error>> {
+        self.post_clock_action("clock_out", &serde_json::json!({})).await
+    }
+
+    async fn post_clock_action<B: Serialize>(
+        &self,
+        action: &str,
+        body: &B,
+    ) -> Result<TimsheetEntry, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/api/gateway.php/{}/v1/time_tracking/employees/{}/{}",
+            self.config.bamboo_base_url, self.config.company, self.config.employee_id, action
+        );
+
+        let request = self.client.post(url).json(body);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Request {} failed with status {:?}: {}",
+                action,
+                response.status(),
+                response.text().await.unwrap_or_default()
+            )
+            .into());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    async fn get_request<T: DeserializeOwned>(
+        &self,
+        url: Url,
+    ) -> Result<Option<T>, Box<dyn std::error::Error>> {
+        let response = self.send(self.client.get(url)).await?;
 
         if !response.status().is_success() {
             return Err(format!(
@@ -199,15 +327,70 @@ impl<'a> BambooClient<'a> {
         }
 
         let response_text = response.text().await?;
-
-        // println!("{:?}", &response_text);
+        tracing::trace!(body = %response_text, "Response body");
 
         match serde_json::from_str(&response_text) {
             Ok(resp_json) => Ok(Option::<T>::Some(resp_json)),
             Err(err) => {
-                println!("Error transforming response text to json: {:?}", err);
-                return Err(Box::new(err));
+                tracing::error!(error = %err, "Error transforming response text to json");
+                Err(Box::new(err))
+            }
+        }
+    }
+
+    /// Sends the request with credentials in a span numbered per process. The API key is never recorded.
+    /// Responses are recorded into or replayed from fixture files when configured.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, Box<dyn std::error::Error>> {
+        let request = request
+            .header("Accept", "application/json")
+            .basic_auth(self.config.api_key.expose(), Option::<String>::None)
+            .build()?;
+        let span = tracing::debug_span!(
+            "bamboo_request",
+            request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
+            method = %request.method(),
+            url = %request.url(),
+            auth = "Basic [redacted]",
+        );
+
+        async {
+            if let Some(FixtureMode::Replay { dir, calls }) = &self.fixtures {
+                tracing::debug!(%dir, "Replaying fixture");
+                let call = {
+                    let mut calls = calls.lock().unwrap();
+                    let call = calls.entry(fixture_name(request.method().as_str(), request.url().path())).or_default();
+                    *call += 1;
+                    *call - 1
+                };
+                return Fixture::load(dir, request.method(), request.url(), call)?.into_response();
+            }
+            let (method, url) = (request.method().clone(), request.url().clone());
+            let started = Instant::now();
+            tracing::debug!("Sending request");
+            let result = self.client.execute(request).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            match &result {
+                Ok(response) => tracing::debug!(status = response.status().as_u16(), elapsed_ms, "Response received"),
+                Err(e) => tracing::error!(error = %e, elapsed_ms, "Error while sending request"),
             }
+            let response = result?;
+
+            let Some(FixtureMode::Record { dir, pseudonyms, recorded }) = &self.fixtures else {
+                return Ok(response);
+            };
+            // only the saved copy is anonymized, the command itself gets the real response
+            let fixture = Fixture::new(&method, &url, response.status().as_u16(), &response.text().await?);
+            let mut anonymized = fixture.clone();
+            anonymize(&mut anonymized.body, &mut pseudonyms.lock().unwrap());
+            // held while saving, so concurrent responses of one endpoint don't overwrite each other
+            let mut recorded = recorded.lock().unwrap();
+            let keep_recorded = !recorded.insert(fixture_name(&fixture.method, &fixture.path));
+            anonymized.save(dir, keep_recorded)?;
+            drop(recorded);
+            tracing::debug!(%dir, path = %fixture.path, "Recorded fixture");
+            fixture.into_response()
         }
+        .instrument(span)
+        .await
     }
 }
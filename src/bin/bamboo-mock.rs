@@ -0,0 +1,480 @@
+//! In-memory BambooHR API for local development. Implements the endpoints used by the bot:
+//! timesheet entries, clock entry store/delete, clock in/out, time off requests, types and balances,
+//! who's out and the employee directory.
+//! Point `BAMBOO_BASE_URL` at it to run the whole bot without touching production.
+
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{Local, NaiveTime};
+use clap::Parser;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "In-memory BambooHR API for local development", long_about = None)]
+struct MockArguments {
+    /// JSON file with `employees`, `timesheetEntries`, `timeOffRequests`, `timeOffTypes`, `timeOffBalances`
+    /// and `whosOut` (holidays) to start with (optional)
+    #[arg(short, long)]
+    seed: Option<String>,
+
+    /// Address to listen on
+    #[arg(short, long, default_value = "127.0.0.1:8081")]
+    listen: String,
+}
+
+/// Everything the mock knows, responses are built from it on every request
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MockState {
+    employees: Vec<Value>,
+    timesheet_entries: Vec<Value>,
+    time_off_requests: Vec<Value>,
+    time_off_types: Vec<Value>,
+    /// Calculator responses with the `employeeId` they belong to
+    time_off_balances: Vec<Value>,
+    whos_out: Vec<Value>,
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+#[derive(Deserialize, Debug)]
+struct EndQuery {
+    end: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RangeQuery {
+    start: String,
+    end: String,
+    employee_ids: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = MockArguments::parse();
+    let state = match &args.seed {
+        Some(seed) => serde_json::from_str(&fs::read_to_string(seed).map_err(|e| format!("Error reading seed file {seed}: {e}"))?)?,
+        None => MockState::default(),
+    };
+
+    let listener = tokio::net::TcpListener::bind(&args.listen).await?;
+    println!("BambooHR mock listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+fn router(state: MockState) -> Router {
+    let api = Router::new()
+        .route("/time_tracking/timesheet_entries", get(get_timesheet_entries))
+        .route("/time_tracking/clock_entries/store", post(store_clock_entries))
+        .route("/time_tracking/clock_entries/delete", post(delete_clock_entries))
+        .route("/time_tracking/employees/:employee_id/clock_in", post(clock_in))
+        .route("/time_tracking/employees/:employee_id/clock_out", post(clock_out))
+        .route("/time_off/requests", get(get_timeoff_requests))
+        .route("/time_off/requests/:id/status", put(change_timeoff_status))
+        .route("/employees/:employee_id/time_off/request", put(add_timeoff_request))
+        .route("/employees/:employee_id/time_off/calculator", get(get_timeoff_balances))
+        .route("/meta/time_off/types", get(get_timeoff_types))
+        .route("/time_off/whos_out", get(get_whos_out))
+        .route("/employees/directory", get(get_employee_directory));
+
+    Router::new()
+        .nest("/api/gateway.php/:company/v1", api)
+        .layer(middleware::from_fn(require_auth))
+        .with_state(Arc::new(Mutex::new(state)))
+}
+
+/// Any API key is accepted, but like BambooHR the mock refuses requests without one
+async fn require_auth(request: Request, next: Next) -> Response {
+    if !request.headers().contains_key(AUTHORIZATION) {
+        return (StatusCode::UNAUTHORIZED, "Missing API key").into_response();
+    }
+    next.run(request).await
+}
+
+async fn get_timesheet_entries(State(state): State<SharedState>, Query(query): Query<RangeQuery>) -> Json<Vec<Value>> {
+    let employee_ids: Vec<String> = query.employee_ids.as_deref().unwrap_or_default().split(',').map(String::from).collect();
+    let state = state.lock().unwrap();
+    Json(
+        state
+            .timesheet_entries
+            .iter()
+            .filter(|entry| in_range(text(entry, "date"), &query))
+            .filter(|entry| employee_ids.contains(&entry["employeeId"].to_string()))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Creates entries, or updates them when `id` is set
+async fn store_clock_entries(State(state): State<SharedState>, Json(body): Json<Value>) -> Response {
+    let mut state = state.lock().unwrap();
+    let mut stored = Vec::new();
+    for entry in body["entries"].as_array().cloned().unwrap_or_default() {
+        let Some(hours) = hours(text(&entry, "start"), text(&entry, "end")) else {
+            return (StatusCode::BAD_REQUEST, format!("Invalid start or end of entry {entry}")).into_response();
+        };
+        let id = match entry["id"].as_u64() {
+            Some(id) => {
+                state.timesheet_entries.retain(|existing| existing["id"].as_u64() != Some(id));
+                id
+            }
+            None => next_id(&state.timesheet_entries),
+        };
+        let stored_entry = json!({
+            "id": id,
+            "employeeId": entry["employeeId"],
+            "type": "clock",
+            "date": entry["date"],
+            "start": entry["start"],
+            "end": entry["end"],
+            "timezone": "UTC",
+            "hours": hours,
+            "note": entry["note"],
+        });
+        state.timesheet_entries.push(stored_entry.clone());
+        stored.push(stored_entry);
+    }
+    (StatusCode::CREATED, Json(stored)).into_response()
+}
+
+/// Starts an open clock entry now, refused while the employee is clocked in
+async fn clock_in(State(state): State<SharedState>, Path((_, employee_id)): Path<(String, String)>, Json(body): Json<Value>) -> Response {
+    let mut state = state.lock().unwrap();
+    if open_entry(&mut state.timesheet_entries, &employee_id).is_some() {
+        return (StatusCode::BAD_REQUEST, "Employee is already clocked in").into_response();
+    }
+    let entry = json!({
+        "id": next_id(&state.timesheet_entries),
+        "employeeId": employee_id.parse::<i32>().unwrap_or_default(),
+        "type": "clock",
+        "date": today(),
+        "start": Local::now().format("%H:%M").to_string(),
+        "end": null,
+        "timezone": "UTC",
+        "hours": 0,
+        "note": body["note"],
+    });
+    state.timesheet_entries.push(entry.clone());
+    Json(entry).into_response()
+}
+
+/// Closes the open clock entry now
+async fn clock_out(State(state): State<SharedState>, Path((_, employee_id)): Path<(String, String)>) -> Response {
+    let mut state = state.lock().unwrap();
+    let Some(entry) = open_entry(&mut state.timesheet_entries, &employee_id) else {
+        return (StatusCode::BAD_REQUEST, "Employee is not clocked in").into_response();
+    };
+    let end = Local::now().format("%H:%M").to_string();
+    entry["hours"] = json!(hours(text(entry, "start"), &end).unwrap_or_default());
+    entry["end"] = json!(end);
+    Json(entry.clone()).into_response()
+}
+
+async fn delete_clock_entries(State(state): State<SharedState>, Json(body): Json<Value>) -> StatusCode {
+    let ids = body["clockEntryIds"].as_array().cloned().unwrap_or_default();
+    state.lock().unwrap().timesheet_entries.retain(|entry| !ids.contains(&entry["id"]));
+    StatusCode::OK
+}
+
+async fn get_timeoff_requests(State(state): State<SharedState>, Query(query): Query<RangeQuery>) -> Json<Vec<Value>> {
+    let state = state.lock().unwrap();
+    Json(state.time_off_requests.iter().filter(|request| overlaps(request, &query)).cloned().collect())
+}
+
+async fn add_timeoff_request(
+    State(state): State<SharedState>,
+    Path((_, employee_id)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    let mut state = state.lock().unwrap();
+    let id = next_id(&state.time_off_requests).to_string();
+    let name = state
+        .employees
+        .iter()
+        .find(|employee| text(employee, "id") == employee_id)
+        .map(|employee| text(employee, "displayName").to_string())
+        .unwrap_or_else(|| format!("Employee {employee_id}"));
+    let time_off_type = state
+        .time_off_types
+        .iter()
+        .find(|time_off_type| text(time_off_type, "id") == text(&body, "timeOffTypeId"))
+        .cloned()
+        .unwrap_or_else(|| json!({ "name": "Time off", "icon": "palm-trees", "units": "days" }));
+    let status = json!({ "lastChanged": today(), "lastChangedByUserId": employee_id, "status": body["status"] });
+    let dates: HashMap<String, String> = body["dates"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|date| (text(date, "ymd").to_string(), date["amount"].to_string()))
+        .collect();
+    let notes: HashMap<String, String> = body["notes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|note| (text(note, "from").to_string(), text(note, "note").to_string()))
+        .collect();
+    state.time_off_requests.push(json!({
+        "id": id,
+        "employeeId": employee_id,
+        "name": name,
+        "status": status,
+        "start": body["start"],
+        "end": body["end"],
+        "created": today(),
+        "type": { "id": body["timeOffTypeId"], "name": time_off_type["name"], "icon": time_off_type["icon"] },
+        "amount": { "unit": time_off_type["units"], "amount": body["amount"].to_string() },
+        "dates": dates,
+        "notes": notes,
+    }));
+    Json(json!({ "id": id, "status": status }))
+}
+
+async fn change_timeoff_status(
+    State(state): State<SharedState>,
+    Path((_, id)): Path<(String, String)>,
+    Json(body): Json<Value>,
+) -> StatusCode {
+    let mut state = state.lock().unwrap();
+    let Some(request) = state.time_off_requests.iter_mut().find(|request| text(request, "id") == id) else {
+        return StatusCode::NOT_FOUND;
+    };
+    request["status"]["status"] = body["status"].clone();
+    request["status"]["lastChanged"] = json!(today());
+    StatusCode::OK
+}
+
+/// Seeded entries (holidays) and approved time off requests in the range
+async fn get_whos_out(State(state): State<SharedState>, Query(query): Query<RangeQuery>) -> Json<Vec<Value>> {
+    let state = state.lock().unwrap();
+    let time_off = state
+        .time_off_requests
+        .iter()
+        .filter(|request| request["status"]["status"] == "approved")
+        .map(|request| {
+            json!({
+                "id": text(request, "id").parse::<u64>().unwrap_or_default(),
+                "type": "timeOff",
+                "employeeId": text(request, "employeeId").parse::<i32>().ok(),
+                "name": request["name"],
+                "start": request["start"],
+                "end": request["end"],
+            })
+        });
+    Json(state.whos_out.iter().cloned().chain(time_off).filter(|entry| overlaps(entry, &query)).collect())
+}
+
+async fn get_timeoff_types(State(state): State<SharedState>) -> Json<Value> {
+    Json(json!({ "timeOffTypes": state.lock().unwrap().time_off_types }))
+}
+
+/// Seeded balances of the employee, as projected at the requested end
+async fn get_timeoff_balances(
+    State(state): State<SharedState>,
+    Path((_, employee_id)): Path<(String, String)>,
+    Query(query): Query<EndQuery>,
+) -> Json<Vec<Value>> {
+    let state = state.lock().unwrap();
+    Json(
+        state
+            .time_off_balances
+            .iter()
+            .filter(|balance| balance["employeeId"].to_string().trim_matches('"') == employee_id)
+            .map(|balance| {
+                let mut balance = balance.clone();
+                balance["end"] = json!(query.end);
+                balance
+            })
+            .collect(),
+    )
+}
+
+async fn get_employee_directory(State(state): State<SharedState>) -> Json<Value> {
+    Json(json!({ "employees": state.lock().unwrap().employees }))
+}
+
+fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value[key].as_str().unwrap_or_default()
+}
+
+fn in_range(day: &str, query: &RangeQuery) -> bool {
+    query.start.as_str() <= day && day <= query.end.as_str()
+}
+
+fn overlaps(value: &Value, query: &RangeQuery) -> bool {
+    text(value, "start") <= query.end.as_str() && text(value, "end") >= query.start.as_str()
+}
+
+fn next_id(values: &[Value]) -> u64 {
+    values
+        .iter()
+        .filter_map(|value| value["id"].as_u64().or_else(|| value["id"].as_str()?.parse().ok()))
+        .max()
+        .unwrap_or_default()
+        + 1
+}
+
+/// Worked hours rounded to hundredths, like BambooHR reports them
+fn hours(start: &str, end: &str) -> Option<f64> {
+    let start = NaiveTime::parse_from_str(start, "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end, "%H:%M").ok()?;
+    Some(((end - start).num_minutes() as f64 / 60.0 * 100.0).round() / 100.0)
+}
+
+fn open_entry<'a>(entries: &'a mut [Value], employee_id: &str) -> Option<&'a mut Value> {
+    entries
+        .iter_mut()
+        .find(|entry| entry["employeeId"].as_i64() == employee_id.parse().ok() && entry["start"].is_string() && entry["end"].is_null())
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn start(state: MockState) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        format!("http://{address}/api/gateway.php/acme/v1")
+    }
+
+    #[tokio::test]
+    async fn test_store_list_and_delete_entries() {
+        let api = start(MockState::default()).await;
+        let client = reqwest::Client::new();
+        let entries_url = format!("{api}/time_tracking/timesheet_entries?start=2024-08-12&end=2024-08-16&employeeIds=12");
+
+        let stored = client
+            .post(format!("{api}/time_tracking/clock_entries/store"))
+            .basic_auth("key", Option::<String>::None)
+            .json(&json!({ "entries": [
+                { "employeeId": 12, "date": "2024-08-12", "start": "08:00", "end": "16:00" },
+                { "employeeId": 13, "date": "2024-08-12", "start": "08:00", "end": "16:00" },
+                { "employeeId": 12, "date": "2024-08-13", "start": "08:00", "end": "15:30" },
+            ]}))
+            .send()
+            .await
+            .unwrap();
+        let listed: Vec<Value> = client.get(&entries_url).basic_auth("key", Option::<String>::None).send().await.unwrap().json().await.unwrap();
+        client
+            .post(format!("{api}/time_tracking/clock_entries/delete"))
+            .basic_auth("key", Option::<String>::None)
+            .json(&json!({ "clockEntryIds": [1] }))
+            .send()
+            .await
+            .unwrap();
+        let after_delete: Vec<Value> = client.get(&entries_url).basic_auth("key", Option::<String>::None).send().await.unwrap().json().await.unwrap();
+
+        assert_eq!(stored.status(), 201);
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0]["id"], 1);
+        assert_eq!(listed[0]["hours"], 8.0);
+        assert_eq!(listed[1]["hours"], 7.5);
+        assert_eq!(after_delete.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_whos_out_includes_approved_requests() {
+        let state = MockState {
+            whos_out: vec![json!({ "id": 1, "type": "holiday", "name": "Assumption Day", "start": "2024-08-15", "end": "2024-08-15" })],
+            time_off_requests: vec![
+                json!({ "id": "7", "employeeId": "12", "name": "Person 1", "status": { "status": "approved" }, "start": "2024-08-13", "end": "2024-08-14" }),
+                json!({ "id": "8", "employeeId": "13", "name": "Person 2", "status": { "status": "requested" }, "start": "2024-08-13", "end": "2024-08-13" }),
+            ],
+            ..Default::default()
+        };
+        let api = start(state).await;
+
+        let whos_out: Vec<Value> = reqwest::Client::new()
+            .get(format!("{api}/time_off/whos_out?start=2024-08-14&end=2024-08-20"))
+            .basic_auth("key", Option::<String>::None)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(whos_out.len(), 2);
+        assert_eq!(whos_out[1], json!({ "id": 7, "type": "timeOff", "employeeId": 12, "name": "Person 1", "start": "2024-08-13", "end": "2024-08-14" }));
+    }
+
+    #[tokio::test]
+    async fn test_clock_in_and_out() {
+        let api = start(MockState::default()).await;
+        let client = reqwest::Client::new();
+        let post = |action: &str| {
+            client
+                .post(format!("{api}/time_tracking/employees/12/{action}"))
+                .basic_auth("key", Option::<String>::None)
+                .json(&json!({}))
+                .send()
+        };
+
+        let clocked_in: Value = post("clock_in").await.unwrap().json().await.unwrap();
+        let second_clock_in = post("clock_in").await.unwrap();
+        let clocked_out: Value = post("clock_out").await.unwrap().json().await.unwrap();
+        let second_clock_out = post("clock_out").await.unwrap();
+
+        assert_eq!(clocked_in["employeeId"], 12);
+        assert!(clocked_in["end"].is_null());
+        assert_eq!(second_clock_in.status(), 400);
+        assert_eq!(clocked_out["id"], clocked_in["id"]);
+        assert!(clocked_out["end"].is_string());
+        assert_eq!(second_clock_out.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_timeoff_types_and_balances() {
+        let state = MockState {
+            time_off_types: vec![json!({ "id": "78", "name": "Vacation", "icon": "palm-trees", "units": "days" })],
+            time_off_balances: vec![
+                json!({ "employeeId": "12", "timeOffType": "78", "name": "Vacation", "units": "days", "balance": "13.5" }),
+                json!({ "employeeId": "13", "timeOffType": "78", "name": "Vacation", "units": "days", "balance": "2" }),
+            ],
+            ..Default::default()
+        };
+        let api = start(state).await;
+        let client = reqwest::Client::new();
+        let get = |path: &str| client.get(format!("{api}/{path}")).basic_auth("key", Option::<String>::None).send();
+
+        let types: Value = get("meta/time_off/types").await.unwrap().json().await.unwrap();
+        let balances: Vec<Value> = get("employees/12/time_off/calculator?end=2024-12-31").await.unwrap().json().await.unwrap();
+
+        assert_eq!(types["timeOffTypes"][0]["name"], "Vacation");
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0]["balance"], "13.5");
+        assert_eq!(balances[0]["end"], "2024-12-31");
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_api_key_are_refused() {
+        let api = start(MockState::default()).await;
+
+        let response = reqwest::get(format!("{api}/employees/directory")).await.unwrap();
+
+        assert_eq!(response.status(), 401);
+    }
+}
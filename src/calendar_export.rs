@@ -0,0 +1,70 @@
+use std::fs;
+
+use chrono::{NaiveDate, Utc};
+
+use crate::{
+    bambo_processor::TimesheetStatus,
+    entry_planner::parse_time,
+    ics::{write_calendar, EventTime, ExportEvent},
+};
+
+/// Turns submitted entries, planned entries, vacations and holidays into calendar events
+pub fn build_export_events(status: &TimesheetStatus, company: &str) -> Vec<ExportEvent> {
+    let mut events = Vec::new();
+
+    for entry in &status.timesheet_entries {
+        let Ok(date) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let times = entry.start.as_deref().and_then(parse_time).zip(entry.end.as_deref().and_then(parse_time));
+        let (summary, time) = match times {
+            Some((start, end)) => ("Timesheet entry".to_string(), EventTime::Timed(date.and_time(start), date.and_time(end))),
+            None => (format!("Timesheet entry ({}h)", entry.hours), EventTime::AllDay(date)),
+        };
+        events.push(ExportEvent {
+            uid: format!("bamboo-bot-entry-{}@{company}", entry.id),
+            summary,
+            time,
+        });
+    }
+
+    for (i, entry) in status.entry_plan.entries.iter().enumerate() {
+        let times = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")
+            .ok()
+            .zip(parse_time(&entry.start))
+            .zip(parse_time(&entry.end));
+        if let Some(((date, start), end)) = times {
+            events.push(ExportEvent {
+                uid: format!("bamboo-bot-planned-{}-{i}@{company}", entry.date),
+                summary: "Planned timesheet entry".to_string(),
+                time: EventTime::Timed(date.and_time(start), date.and_time(end)),
+            });
+        }
+    }
+
+    let all_day_events = [
+        ("vacation", "Vacation", &status.vacation_days),
+        ("holiday", "Bank holiday", &status.bank_holidays),
+        ("out-of-office", "Out of office", &status.out_of_office_days),
+    ];
+    for (kind, summary, days) in all_day_events {
+        for day in days {
+            if let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+                events.push(ExportEvent {
+                    uid: format!("bamboo-bot-{kind}-{day}@{company}"),
+                    summary: summary.to_string(),
+                    time: EventTime::AllDay(date),
+                });
+            }
+        }
+    }
+
+    events
+}
+
+pub fn export_calendar(status: &TimesheetStatus, company: &str, output: &str) -> Result<usize, String> {
+    let events = build_export_events(status, company);
+    fs::write(output, write_calendar(&events, Utc::now()))
+        .map_err(|e| format!("Error while writing calendar to {output}: {e}"))?;
+    Ok(events.len())
+}
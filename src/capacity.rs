@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    days_calculator::{format_date, DateRange},
+    model::get_timeoff_requests::timeoff_request::TimeOffRequest,
+    work_schedule::WorkSchedule,
+};
+
+/// Request statuses which take time away from the sprint
+const COUNTED_STATUSES: [&str; 2] = ["approved", "requested"];
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmployeeCapacity {
+    pub employee_id: i32,
+    pub name: String,
+    pub working_days: usize,
+    pub time_off_hours: f32,
+    pub available_hours: f32,
+    pub available_days: f32,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SprintCapacity {
+    pub from: String,
+    pub to: String,
+    pub employees: Vec<EmployeeCapacity>,
+    pub total_available_hours: f32,
+    pub total_available_days: f32,
+}
+
+impl SprintCapacity {
+    pub fn new(range: &DateRange, employees: Vec<EmployeeCapacity>) -> Self {
+        SprintCapacity {
+            from: format_date(&range.start),
+            to: format_date(&range.end),
+            total_available_hours: employees.iter().map(|employee| employee.available_hours).sum(),
+            total_available_days: employees.iter().map(|employee| employee.available_days).sum(),
+            employees,
+        }
+    }
+}
+
+/// Working hours of the employee in the range without bank holidays and time off, partial days included
+pub fn calculate_capacity(
+    range: &DateRange,
+    employee_id: i32,
+    name: &str,
+    schedule: &WorkSchedule,
+    bank_holidays: &[String],
+    requests: &[&TimeOffRequest],
+) -> EmployeeCapacity {
+    let daily_hours = schedule.daily_hours();
+    let working_days: Vec<String> = range
+        .days()
+        .filter(|day| schedule.is_working_day(day))
+        .map(|day| format_date(&day))
+        .filter(|day| !bank_holidays.contains(day))
+        .collect();
+
+    let mut time_off_per_day: HashMap<&str, f32> = HashMap::new();
+    for request in requests
+        .iter()
+        .filter(|request| COUNTED_STATUSES.contains(&request.status.status.as_str()))
+    {
+        let hours_per_unit = if request.amount.unit == "hours" { 1.0 } else { daily_hours };
+        for (day, amount) in request.dates.iter().flatten() {
+            if !working_days.contains(day) {
+                continue;
+            }
+            if let Ok(amount) = amount.parse::<f32>() {
+                *time_off_per_day.entry(day).or_default() += amount * hours_per_unit;
+            }
+        }
+    }
+    let time_off_hours: f32 = time_off_per_day.values().map(|hours| hours.min(daily_hours)).sum();
+    let available_hours = working_days.len() as f32 * daily_hours - time_off_hours;
+
+    EmployeeCapacity {
+        employee_id,
+        name: String::from(name),
+        working_days: working_days.len(),
+        time_off_hours,
+        available_hours,
+        available_days: available_hours / daily_hours,
+    }
+}
+
+/// Parses "ID=WORKING_DAYS[:FTE]", e.g. "12=mon-thu:0.8"
+pub fn parse_part_time(value: &str, work_start: &str) -> Result<(i32, WorkSchedule), String> {
+    let invalid = || format!("Invalid part-time schedule {value}, expected e.g. 12=mon-thu:0.8");
+    let (employee_id, schedule) = value.split_once('=').ok_or_else(invalid)?;
+    let employee_id = employee_id.trim().parse::<i32>().map_err(|_| invalid())?;
+    let (working_days, fte) = match schedule.rsplit_once(':') {
+        Some((working_days, fte)) => (working_days, fte.trim().parse::<f32>().map_err(|_| invalid())?),
+        None => (schedule, 1.0),
+    };
+    Ok((employee_id, WorkSchedule::parse(working_days, fte, work_start)?))
+}
+
+pub fn render_table(capacity: &SprintCapacity) -> String {
+    let name_width = capacity
+        .employees
+        .iter()
+        .map(|employee| employee.name.chars().count())
+        .chain(["Employee".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![
+        format!("Sprint capacity {} - {}", capacity.from, capacity.to),
+        format!("{:<name_width$}  {:>12}  {:>13}  {:>14}  {:>15}", "Employee", "Working days", "Time off (h)", "Available (h)", "Available (days)"),
+    ];
+    for employee in &capacity.employees {
+        lines.push(format!(
+            "{:<name_width$}  {:>12}  {:>13.1}  {:>14.1}  {:>15.1}",
+            employee.name, employee.working_days, employee.time_off_hours, employee.available_hours, employee.available_days
+        ));
+    }
+    lines.push(format!(
+        "{:<name_width$}  {:>12}  {:>13}  {:>14.1}  {:>15.1}",
+        "Total", "", "", capacity.total_available_hours, capacity.total_available_days
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(status: &str, unit: &str, dates: &[(&str, &str)]) -> TimeOffRequest {
+        serde_json::from_value(json!({
+            "id": "1",
+            "employeeId": "12",
+            "name": "Anna Nowak",
+            "status": { "lastChanged": "2024-08-01", "lastChangedByUserId": "1", "status": status },
+            "start": dates[0].0,
+            "end": dates[dates.len() - 1].0,
+            "created": "2024-08-01",
+            "type": { "id": "1", "name": "Vacation", "icon": "palm-trees" },
+            "amount": { "unit": unit, "amount": "1" },
+            "dates": dates.iter().map(|(day, amount)| (day.to_string(), amount.to_string())).collect::<HashMap<String, String>>(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_calculate_capacity() {
+        // two weeks sprint with a bank holiday on thursday 15th
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-23")).unwrap();
+        let holidays = vec!["2024-08-15".to_string()];
+        let vacation = request("approved", "days", &[("2024-08-19", "1"), ("2024-08-20", "0.5")]);
+        let half_day_in_hours = request("requested", "hours", &[("2024-08-21", "4")]);
+        let denied = request("denied", "days", &[("2024-08-22", "1")]);
+
+        let capacity = calculate_capacity(
+            &range,
+            12,
+            "Anna Nowak",
+            &WorkSchedule::default(),
+            &holidays,
+            &[&vacation, &half_day_in_hours, &denied],
+        );
+
+        assert_eq!(capacity.working_days, 9);
+        assert_eq!(capacity.time_off_hours, 16.0);
+        assert_eq!(capacity.available_hours, 56.0);
+        assert_eq!(capacity.available_days, 7.0);
+    }
+
+    #[test]
+    fn test_calculate_part_time_capacity() {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-23")).unwrap();
+        let (_, schedule) = parse_part_time("12=mon-thu:0.8", "08:00").unwrap();
+
+        let capacity = calculate_capacity(&range, 12, "Anna Nowak", &schedule, &[], &[]);
+
+        assert_eq!(capacity.working_days, 8);
+        assert_eq!(capacity.available_hours, 64.0);
+    }
+
+    #[test]
+    fn test_parse_part_time() {
+        assert_eq!(parse_part_time("12=mon-fri", "08:00").unwrap(), (12, WorkSchedule::default()));
+        assert!(parse_part_time("mon-thu:0.8", "08:00").is_err());
+        assert!(parse_part_time("12=mon-thu:most", "08:00").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::NaiveTime;
+use serde::Serialize;
+
+use crate::{entry_planner::parse_time, model::get_timesheet_entries::timesheet_entry::TimsheetEntry};
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockStatus {
+    pub clocked_in_since: Option<NaiveTime>,
+    pub project: Option<String>,
+    pub worked_minutes: i64,
+}
+
+/// An entry of the day without an end means the employee is clocked in, it's counted until `now`
+pub fn clock_status(entries: &[TimsheetEntry], day: &str, now: NaiveTime) -> ClockStatus {
+    let mut status = ClockStatus { clocked_in_since: None, project: None, worked_minutes: 0 };
+    for entry in entries.iter().filter(|entry| entry.date == day) {
+        let Some(start) = entry.start.as_deref().and_then(parse_time) else {
+            continue;
+        };
+        let end = match entry.end.as_deref() {
+            Some(end) => parse_time(end).unwrap_or(start),
+            None => {
+                status.clocked_in_since = Some(start);
+                status.project = entry
+                    .project_info
+                    .as_ref()
+                    .map(|info| format!("{} / {}", info.project.name, info.task.name));
+                now.max(start)
+            }
+        };
+        status.worked_minutes += (end - start).num_minutes();
+    }
+    status
+}
+
+pub fn render_status(status: &ClockStatus) -> String {
+    let worked = format!("worked today {}h {:02}m", status.worked_minutes / 60, status.worked_minutes % 60);
+    match (status.clocked_in_since, &status.project) {
+        (Some(since), Some(project)) => format!("Clocked in since {} on {project}, {worked}", since.format("%H:%M")),
+        (Some(since), None) => format!("Clocked in since {}, {worked}", since.format("%H:%M")),
+        (None, _) => format!("Clocked out, {worked}"),
+    }
+}
+
+/// "HH:MM" of the entry start or end, the raw value when it can't be parsed
+pub fn format_entry_time(value: Option<&str>) -> String {
+    let value = value.unwrap_or_default();
+    parse_time(value)
+        .map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, start: &str, end: Option<&str>) -> TimsheetEntry {
+        TimsheetEntry {
+            id: 1,
+            employee_id: 12,
+            r#type: "clock".to_string(),
+            date: date.to_string(),
+            start: Some(start.to_string()),
+            end: end.map(String::from),
+            timezone: "Europe/Warsaw".to_string(),
+            hours: 0.0,
+            note: None,
+            project_info: None,
+            approved_at: None,
+            approved: None,
+        }
+    }
+
+    #[test]
+    fn test_clocked_in() {
+        let entries = vec![
+            entry("2024-08-19", "2024-08-19T08:00:00+02:00", Some("2024-08-19T12:00:00+02:00")),
+            entry("2024-08-19", "2024-08-19T12:30:00+02:00", None),
+            entry("2024-08-16", "2024-08-16T08:00:00+02:00", None),
+        ];
+
+        let status = clock_status(&entries, "2024-08-19", NaiveTime::from_hms_opt(14, 15, 0).unwrap());
+
+        assert_eq!(status.clocked_in_since, NaiveTime::from_hms_opt(12, 30, 0));
+        assert_eq!(status.worked_minutes, 345);
+        assert_eq!(render_status(&status), "Clocked in since 12:30, worked today 5h 45m");
+    }
+
+    #[test]
+    fn test_clocked_out() {
+        let entries = vec![entry("2024-08-19", "08:00", Some("16:00"))];
+
+        let status = clock_status(&entries, "2024-08-19", NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+
+        assert_eq!(render_status(&status), "Clocked out, worked today 8h 00m");
+    }
+}
@@ -1,64 +1,426 @@
 use std::env;
 
-use clap::{arg, command, Parser};
+use clap::{Parser, Subcommand, ValueEnum};
 use derive_builder::Builder;
 use dotenvy::dotenv;
 use serde::Deserialize;
 
+use crate::{email_notifier::SmtpConfig, output::OutputFormat, public_holidays::validate_calendar, secret::Secret, webhook_notifier::{WebhookConfig, WebhookFormat}, work_schedule::WorkSchedule};
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct ProgramArguments {
 
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// BambooHR API key
-    #[arg(short, long)]
-    pub api_key: Option<String>,
+    #[arg(short, long, global = true)]
+    pub api_key: Option<Secret>,
 
     /// BambooHR employee id
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub employee_id: Option<i32>,
 
     /// Company name
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub company: Option<String>,
 
     /// BambooHR base url (optional)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub bamboo_base_url: Option<String>,
+
+    /// Country of built-in public holiday calendar, e.g. PL or DE (optional)
+    #[arg(long, global = true)]
+    pub holiday_country: Option<String>,
+
+    /// Region of built-in public holiday calendar, e.g. BY for Bavaria (optional)
+    #[arg(long, global = true)]
+    pub holiday_region: Option<String>,
+
+    /// File with additional holidays, one "YYYY-MM-DD name" per line (optional)
+    #[arg(long, global = true)]
+    pub holidays_file: Option<String>,
+
+    /// iCalendar file or directory of .ics files with out of office events (optional)
+    #[arg(long, global = true)]
+    pub ics_path: Option<String>,
+
+    /// Working weekdays, e.g. mon-fri, "mon,tue,wed,thu" or alternating weeks "mon-fri;mon-thu" (optional)
+    #[arg(long, global = true)]
+    pub working_days: Option<String>,
+
+    /// Full-time equivalent used to compute daily hours, e.g. 0.8 (optional)
+    #[arg(long, global = true)]
+    pub fte: Option<f32>,
+
+    /// Start of the working day, e.g. 08:00 (optional)
+    #[arg(long, global = true)]
+    pub work_start: Option<String>,
+
+    /// SMTP server for email notifications (optional)
+    #[arg(long, global = true)]
+    pub smtp_host: Option<String>,
+
+    /// SMTP port, defaults to 587
+    #[arg(long, global = true)]
+    pub smtp_port: Option<u16>,
+
+    /// SMTP username (optional)
+    #[arg(long, global = true)]
+    pub smtp_username: Option<String>,
+
+    /// SMTP password (optional)
+    #[arg(long, global = true)]
+    pub smtp_password: Option<Secret>,
+
+    /// Sender of email notifications
+    #[arg(long, global = true)]
+    pub smtp_from: Option<String>,
+
+    /// Recipient of email notifications
+    #[arg(long, global = true)]
+    pub smtp_to: Option<String>,
+
+    /// Use STARTTLS, defaults to true. Disable only for local SMTP servers
+    #[arg(long, global = true)]
+    pub smtp_tls: Option<bool>,
+
+    /// Slack or Teams incoming webhook url for notifications (optional)
+    #[arg(long, global = true)]
+    pub webhook_url: Option<Secret>,
+
+    /// Message format of the webhook, defaults to slack
+    #[arg(long, global = true)]
+    pub webhook_format: Option<WebhookFormat>,
+
+    /// Employee ids of the team, e.g. 12,15,21 (optional)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub team: Vec<i32>,
+
+    /// Output format, json prints structured results on stdout and logs on stderr (optional)
+    #[arg(long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Review and edit the month in a terminal UI instead of answering y/n questions when filling
+    #[arg(long, global = true)]
+    pub tui: bool,
+
+    /// More detailed logs on stderr, -v for requests, -vv for response bodies. RUST_LOG takes precedence
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// File locked while entries are submitted, defaults to one in the temp directory (optional)
+    #[arg(long, global = true)]
+    pub lock_file: Option<String>,
+
+    /// Save anonymized BambooHR responses into fixture files in this directory (optional)
+    #[arg(long, global = true)]
+    pub record_fixtures: Option<String>,
+
+    /// Serve BambooHR responses from fixture files in this directory instead of the network (optional)
+    #[arg(long, global = true)]
+    pub replay_fixtures: Option<String>,
+
+    /// Password of the web dashboard, required when it listens on other than a loopback address (optional)
+    #[arg(long, global = true)]
+    pub dashboard_password: Option<Secret>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Fill missing days of the current month (default)
+    Fill {
+        /// First day of the range (YYYY-MM-DD), defaults to the first day of the current month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Send entries without asking
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Export planned and submitted entries, vacations and bank holidays to an .ics file
+    Export {
+        /// First day of the range (YYYY-MM-DD), defaults to the first day of the current month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Output file
+        #[arg(short, long, default_value = "timesheet.ics")]
+        output: String,
+    },
+    /// Save entries which fill would add into a plan file for review
+    Plan {
+        /// First day of the range (YYYY-MM-DD), defaults to the first day of the current month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Plan file
+        #[arg(short, long, default_value = "timesheet-plan.json")]
+        output: String,
+    },
+    /// Execute a saved plan file unless the timesheet changed since planning
+    Apply {
+        /// Plan file
+        #[arg(default_value = "timesheet-plan.json")]
+        plan: String,
+    },
+    /// Run a local web dashboard with the month status, team report and one-click fill
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+    /// Keep running and fill the current month without asking, on schedule
+    Daemon {
+        /// When to fill: weekdays@HH:MM or last-working-day@HH:MM
+        #[arg(long, default_value = "weekdays@17:00")]
+        trigger: String,
+        /// When to send a reminder with days missing this month, e.g. last-working-day@09:00 (optional)
+        #[arg(long)]
+        remind_trigger: Option<String>,
+        /// File where outcomes of runs are kept between restarts
+        #[arg(long, default_value = "bamboo-bot-state.json")]
+        state_file: String,
+    },
+    /// Send a reminder with days missing in the current month to configured notifiers
+    Remind,
+    /// Report days missing in timesheets of the team
+    TeamReport {
+        /// First day of the range (YYYY-MM-DD), defaults to the first day of the current month
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        to: Option<String>,
+        /// Report direct reports of the configured employee found in the directory instead of --team
+        #[arg(long)]
+        direct_reports: bool,
+    },
+    /// Show who is out as a calendar grid, only members of --team are shown when it's set
+    WhosOut {
+        /// First day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to two weeks from the first day
+        #[arg(long)]
+        to: Option<String>,
+        /// Show only employees of the department from the directory
+        #[arg(long)]
+        department: Option<String>,
+    },
+    /// Compute available person-days and hours of the --team in a sprint
+    Capacity {
+        /// First day of the sprint (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        /// Last day of the sprint (YYYY-MM-DD)
+        #[arg(long)]
+        to: String,
+        /// Part-time schedule of an employee as ID=WORKING_DAYS[:FTE], e.g. 12=mon-thu:0.8. Can be repeated
+        #[arg(long)]
+        part_time: Vec<String>,
+        /// Output format, json is the same as --output=json
+        #[arg(long, value_enum, default_value_t = CapacityFormat::Table)]
+        format: CapacityFormat,
+    },
+    /// Show time off balance per policy, days approved this year and projected balance at year end
+    Balance,
+    /// Submit a time off request for working days in the range
+    RequestTimeoff {
+        /// Time off type ID or name, e.g. Vacation
+        #[arg(long = "type")]
+        time_off_type: String,
+        /// First day of time off (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        /// Last day of time off (YYYY-MM-DD), defaults to the first day
+        #[arg(long)]
+        to: Option<String>,
+        /// Amount on a single working day of the range as YYYY-MM-DD=AMOUNT, in the units of the type:
+        /// 2024-08-19=0.5 is a half day for types counted in days, 2024-08-19=4 is four hours for types counted in hours. Can be repeated
+        #[arg(long)]
+        day: Vec<String>,
+        /// Note for the approver
+        #[arg(long)]
+        note: Option<String>,
+        /// Submit the request without asking
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Approve or deny pending time off requests, one by one unless --approve-all or --deny-all is set
+    Approvals {
+        /// First day of the range (YYYY-MM-DD), defaults to today
+        #[arg(long)]
+        from: Option<String>,
+        /// Last day of the range (YYYY-MM-DD), defaults to a year from the first day
+        #[arg(long)]
+        to: Option<String>,
+        /// Approve all pending requests without asking
+        #[arg(long, conflicts_with = "deny_all")]
+        approve_all: bool,
+        /// Deny all pending requests without asking
+        #[arg(long)]
+        deny_all: bool,
+        /// Note sent with every decision
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Clock in now
+    ClockIn {
+        /// Project ID
+        #[arg(long)]
+        project: Option<u32>,
+        /// Task ID of the project
+        #[arg(long, requires = "project")]
+        task: Option<u32>,
+        /// Note of the entry
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Clock out now
+    ClockOut,
+    /// Show whether you are clocked in and hours worked today
+    Status,
+    /// Move all clock entries of a day earlier or later
+    ShiftDay {
+        /// Day of the entries (YYYY-MM-DD)
+        #[arg(long)]
+        date: String,
+        /// Shift as [+-]HH:MM, e.g. -00:30 to start half an hour earlier
+        #[arg(long, allow_hyphen_values = true)]
+        by: String,
+    },
+    /// Delete clock entries in the range
+    DeleteEntries {
+        /// First day of the range (YYYY-MM-DD)
+        #[arg(long)]
+        from: String,
+        /// Last day of the range (YYYY-MM-DD), defaults to the first day
+        #[arg(long)]
+        to: Option<String>,
+        /// Delete without asking
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum CapacityFormat {
+    Table,
+    Json,
 }
 
-#[derive(Builder)]
+#[derive(Builder, Clone)]
 pub struct Config {
-    pub api_key: String,
+    #[builder(setter(into))]
+    pub api_key: Secret,
     pub employee_id: i32,
     pub company: String,
     #[builder(default = "String::from(\"https://api.bamboohr.com\")")]
     pub bamboo_base_url: String,
+    #[builder(default)]
+    pub holiday_country: Option<String>,
+    #[builder(default)]
+    pub holiday_region: Option<String>,
+    #[builder(default)]
+    pub holidays_file: Option<String>,
+    #[builder(default)]
+    pub ics_path: Option<String>,
+    #[builder(default)]
+    pub schedule: WorkSchedule,
+    #[builder(default)]
+    pub smtp: Option<SmtpConfig>,
+    #[builder(default)]
+    pub webhook: Option<WebhookConfig>,
+    #[builder(default)]
+    pub team: Vec<i32>,
+    #[builder(default)]
+    pub lock_file: Option<String>,
+    #[builder(default)]
+    pub record_fixtures: Option<String>,
+    #[builder(default)]
+    pub replay_fixtures: Option<String>,
+    #[builder(default)]
+    pub dashboard_password: Option<Secret>,
+    #[builder(default)]
+    pub output: OutputFormat,
 }
 
 #[derive(Deserialize, Debug)]
 
 pub struct EnvironmentVariables {
-    pub api_key: Option<String>,
+    pub api_key: Option<Secret>,
     pub employee_id: Option<String>,
     pub company: Option<String>,
     pub bamboo_base_url: Option<String>,
+    pub holiday_country: Option<String>,
+    pub holiday_region: Option<String>,
+    pub holidays_file: Option<String>,
+    pub ics_path: Option<String>,
+    pub working_days: Option<String>,
+    pub fte: Option<String>,
+    pub work_start: Option<String>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<String>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<Secret>,
+    pub smtp_from: Option<String>,
+    pub smtp_to: Option<String>,
+    pub smtp_tls: Option<String>,
+    pub webhook_url: Option<Secret>,
+    pub webhook_format: Option<String>,
+    pub team_employee_ids: Option<String>,
+    pub lock_file: Option<String>,
+    pub output_format: Option<String>,
+    pub record_fixtures: Option<String>,
+    pub replay_fixtures: Option<String>,
+    pub dashboard_password: Option<Secret>,
 }
 
 impl EnvironmentVariables {
     pub fn load_from_env() -> Self {
         EnvironmentVariables {
-            api_key: env::var("API_KEY").ok(),
+            api_key: env::var("API_KEY").ok().map(Secret::from),
             employee_id: env::var("EMPLOYEE_ID").ok(),
             company: env::var("COMPANY").ok(),
             bamboo_base_url: env::var("BAMBOO_BASE_URL").ok(),
+            holiday_country: env::var("HOLIDAY_COUNTRY").ok(),
+            holiday_region: env::var("HOLIDAY_REGION").ok(),
+            holidays_file: env::var("HOLIDAYS_FILE").ok(),
+            ics_path: env::var("ICS_PATH").ok(),
+            working_days: env::var("WORKING_DAYS").ok(),
+            fte: env::var("FTE").ok(),
+            work_start: env::var("WORK_START").ok(),
+            smtp_host: env::var("SMTP_HOST").ok(),
+            smtp_port: env::var("SMTP_PORT").ok(),
+            smtp_username: env::var("SMTP_USERNAME").ok(),
+            smtp_password: env::var("SMTP_PASSWORD").ok().map(Secret::from),
+            smtp_from: env::var("SMTP_FROM").ok(),
+            smtp_to: env::var("SMTP_TO").ok(),
+            smtp_tls: env::var("SMTP_TLS").ok(),
+            webhook_url: env::var("WEBHOOK_URL").ok().map(Secret::from),
+            webhook_format: env::var("WEBHOOK_FORMAT").ok(),
+            team_employee_ids: env::var("TEAM_EMPLOYEE_IDS").ok(),
+            lock_file: env::var("LOCK_FILE").ok(),
+            output_format: env::var("OUTPUT_FORMAT").ok(),
+            record_fixtures: env::var("RECORD_FIXTURES").ok(),
+            replay_fixtures: env::var("REPLAY_FIXTURES").ok(),
+            dashboard_password: env::var("DASHBOARD_PASSWORD").ok().map(Secret::from),
         }
     }
 }
 
-pub fn load_config() -> Result<Config, String> {
+#[allow(clippy::needless_return, clippy::unnecessary_unwrap)]
+pub fn load_config(program_args: &ProgramArguments) -> Result<Config, String> {
     dotenv().ok();
     let mut config_builder = ConfigBuilder::default();
-    let program_args = ProgramArguments::parse();
     let env_config = EnvironmentVariables::load_from_env();
    
     if program_args.api_key.is_none() {
@@ -66,7 +428,7 @@ pub fn load_config() -> Result<Config, String> {
             .api_key
             .ok_or("Missing api key. Use --api-key=<API_KEY> or env variable API_KEY=<API_KEY>".to_string())?);
     } else {
-        config_builder.api_key(program_args.api_key.unwrap());
+        config_builder.api_key(program_args.api_key.clone().unwrap());
     }
 
     if program_args.company.is_none() {
@@ -74,7 +436,7 @@ pub fn load_config() -> Result<Config, String> {
             .company
             .ok_or("Missing company name. Use --company=<COMPANY_NAME> or env variable COMPANY=<COMPANY_NAME>".to_string())?);
     } else {
-        config_builder.company(program_args.company.unwrap());
+        config_builder.company(program_args.company.clone().unwrap());
     }
 
     if program_args.employee_id.is_none() {
@@ -86,12 +448,105 @@ pub fn load_config() -> Result<Config, String> {
         config_builder.employee_id(program_args.employee_id.unwrap());
     }
 
-    if let Some(bamboo_url) = program_args.bamboo_base_url {
-        config_builder.bamboo_base_url(bamboo_url);
+    if let Some(bamboo_url) = &program_args.bamboo_base_url {
+        config_builder.bamboo_base_url(bamboo_url.clone());
     } else if let Some(bamboo_url) = env_config.bamboo_base_url {
         config_builder.bamboo_base_url(bamboo_url);
     }
 
+    let holiday_country = program_args.holiday_country.clone().or(env_config.holiday_country);
+    let holiday_region = program_args.holiday_region.clone().or(env_config.holiday_region);
+    if let Some(country) = &holiday_country {
+        validate_calendar(country, holiday_region.as_deref())?;
+    } else if holiday_region.is_some() {
+        return Err("Holiday region requires a country. Use --holiday-country=<COUNTRY> or env variable HOLIDAY_COUNTRY=<COUNTRY>".to_string());
+    }
+    config_builder.holiday_country(holiday_country);
+    config_builder.holiday_region(holiday_region);
+    config_builder.holidays_file(program_args.holidays_file.clone().or(env_config.holidays_file));
+    config_builder.ics_path(program_args.ics_path.clone().or(env_config.ics_path));
+
+    let fte = match (program_args.fte, env_config.fte) {
+        (Some(fte), _) => fte,
+        (None, Some(fte)) => fte.parse::<f32>().map_err(|_| format!("FTE should be a number, got {fte}"))?,
+        (None, None) => 1.0,
+    };
+    config_builder.schedule(WorkSchedule::parse(
+        &program_args.working_days.clone().or(env_config.working_days).unwrap_or("mon-fri".to_string()),
+        fte,
+        &program_args.work_start.clone().or(env_config.work_start).unwrap_or("08:00".to_string()),
+    )?);
+
+    if let Some(host) = program_args.smtp_host.clone().or(env_config.smtp_host) {
+        let port = match (program_args.smtp_port, env_config.smtp_port) {
+            (Some(port), _) => port,
+            (None, Some(port)) => port.parse::<u16>().map_err(|_| format!("SMTP port should be a number, got {port}"))?,
+            (None, None) => 587,
+        };
+        let tls = match (program_args.smtp_tls, env_config.smtp_tls) {
+            (Some(tls), _) => tls,
+            (None, Some(tls)) => tls.parse::<bool>().map_err(|_| format!("SMTP_TLS should be true or false, got {tls}"))?,
+            (None, None) => true,
+        };
+        let username = program_args.smtp_username.clone().or(env_config.smtp_username);
+        let password = program_args.smtp_password.clone().or(env_config.smtp_password);
+        if username.is_some() != password.is_some() {
+            return Err("SMTP username and password go together. Use both --smtp-username and --smtp-password or env variables SMTP_USERNAME and SMTP_PASSWORD, or neither".to_string());
+        }
+        config_builder.smtp(Some(SmtpConfig {
+            host,
+            port,
+            username,
+            password,
+            from: program_args.smtp_from.clone().or(env_config.smtp_from)
+                .ok_or("Missing email sender. Use --smtp-from=<EMAIL> or env variable SMTP_FROM=<EMAIL>".to_string())?,
+            to: program_args.smtp_to.clone().or(env_config.smtp_to)
+                .ok_or("Missing email recipient. Use --smtp-to=<EMAIL> or env variable SMTP_TO=<EMAIL>".to_string())?,
+            tls,
+        }));
+    }
+
+    if let Some(url) = program_args.webhook_url.clone().or(env_config.webhook_url) {
+        let format = match (program_args.webhook_format, env_config.webhook_format) {
+            (Some(format), _) => format,
+            (None, Some(format)) => WebhookFormat::from_str(&format, true)
+                .map_err(|_| format!("WEBHOOK_FORMAT should be slack or teams, got {format}"))?,
+            (None, None) => WebhookFormat::Slack,
+        };
+        config_builder.webhook(Some(WebhookConfig { url, format }));
+    }
+
+    if !program_args.team.is_empty() {
+        config_builder.team(program_args.team.clone());
+    } else if let Some(team) = env_config.team_employee_ids {
+        config_builder.team(team
+            .split(',')
+            .map(|id| id.trim().parse::<i32>().map_err(|_| format!("TEAM_EMPLOYEE_IDS should be comma separated numbers, got {team}")))
+            .collect::<Result<Vec<i32>, String>>()?);
+    }
+
+    config_builder.lock_file(program_args.lock_file.clone().or(env_config.lock_file));
+
+    let record_fixtures = program_args.record_fixtures.clone().or(env_config.record_fixtures);
+    let replay_fixtures = program_args.replay_fixtures.clone().or(env_config.replay_fixtures);
+    if record_fixtures.is_some() && replay_fixtures.is_some() {
+        return Err("Fixtures can't be recorded and replayed at the same time, use only one of RECORD_FIXTURES and REPLAY_FIXTURES".to_string());
+    }
+    config_builder.record_fixtures(record_fixtures);
+    config_builder.replay_fixtures(replay_fixtures);
+    config_builder.dashboard_password(program_args.dashboard_password.clone().or(env_config.dashboard_password));
+
+    match (program_args.output, env_config.output_format) {
+        (Some(output), _) => {
+            config_builder.output(output);
+        }
+        (None, Some(output)) => {
+            config_builder.output(OutputFormat::from_str(&output, true)
+                .map_err(|_| format!("OUTPUT_FORMAT should be text or json, got {output}"))?);
+        }
+        (None, None) => {}
+    }
+
    match config_builder.build() {
     Ok(config) => return Ok(config),
     Err(_) => return Err("Error when building config".to_string()),
@@ -0,0 +1,310 @@
+use std::fs;
+
+use chrono::{Datelike, Local, NaiveDateTime};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{bambo_processor::BambooProcessor, config::Config, days_calculator::{format_date, DateRange}, notification::{send_notification, Notification}, scheduler::Trigger, work_schedule::WorkSchedule};
+
+const STATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+const KEPT_RUNS: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonState {
+    pub last_run: Option<String>,
+    #[serde(default)]
+    pub last_reminder: Option<String>,
+    pub runs: Vec<RunRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRecord {
+    pub started_at: String,
+    pub success: bool,
+    pub added_entries: usize,
+    pub outcome: String,
+}
+
+impl DaemonState {
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Error while reading state file {path}: {e}")),
+            Err(_) => Ok(DaemonState::default()),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| format!("Error while writing state file {path}: {e}"))
+    }
+
+    /// Keeps the run in the history. Only a successful or given up run moves `last_run` to the trigger
+    /// it was made for, so a failed one is retried.
+    pub fn record(&mut self, run: RunRecord, fired_at: &NaiveDateTime, given_up: bool) {
+        if run.success || given_up {
+            self.last_run = Some(fired_at.format(STATE_TIME_FORMAT).to_string());
+        }
+        self.runs.push(run);
+        if self.runs.len() > KEPT_RUNS {
+            self.runs.drain(..self.runs.len() - KEPT_RUNS);
+        }
+    }
+
+    fn last_run_time(&self) -> Option<NaiveDateTime> {
+        self.last_run
+            .as_deref()
+            .and_then(|last_run| NaiveDateTime::parse_from_str(last_run, STATE_TIME_FORMAT).ok())
+    }
+
+    /// Reminder missed while the daemon was down is only sent while its month is not over
+    fn next_reminder(&self, trigger: &Trigger, now: NaiveDateTime, schedule: &WorkSchedule) -> Option<NaiveDateTime> {
+        let last_reminder = self
+            .last_reminder
+            .as_deref()
+            .and_then(|last_reminder| NaiveDateTime::parse_from_str(last_reminder, STATE_TIME_FORMAT).ok())
+            .unwrap_or(now);
+        match trigger.next_after(last_reminder, schedule)? {
+            reminder if reminder < now && (reminder.year(), reminder.month()) != (now.year(), now.month()) => trigger.next_after(now, schedule),
+            reminder => Some(reminder),
+        }
+    }
+}
+
+/// Fills timesheets whenever the trigger fires. Runs missed while the daemon was down are done right after start,
+/// each for the month it was due in. Failed runs are retried with a growing delay.
+/// Reminders about missing days of the month are sent whenever `remind_trigger` fires.
+pub async fn run_daemon(
+    bambo_processor: &BambooProcessor<'_>,
+    config: &Config,
+    trigger: &Trigger,
+    remind_trigger: Option<&Trigger>,
+    state_file: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut state = DaemonState::load(state_file)?;
+    let mut failures = 0;
+    tracing::info!(?trigger, ?remind_trigger, %state_file, "Daemon started");
+
+    loop {
+        let now = Local::now().naive_local();
+        let next_run = trigger
+            .next_after(state.last_run_time().unwrap_or(now), &config.schedule)
+            .ok_or("Trigger never fires with configured working days")?;
+
+        let wait = if failures > 0 {
+            tracing::info!(%next_run, retry_in_secs = retry_delay(failures).as_secs(), "Retrying failed run");
+            retry_delay(failures)
+        } else if next_run > now {
+            tracing::info!(%next_run, "Waiting for next run");
+            (next_run - now).to_std().unwrap_or_default()
+        } else {
+            std::time::Duration::ZERO
+        };
+        let next_reminder = remind_trigger
+            .and_then(|remind_trigger| state.next_reminder(remind_trigger, now, &config.schedule))
+            .filter(|reminder| *reminder < now + wait);
+        let wait = match next_reminder {
+            Some(reminder) => {
+                tracing::info!(%reminder, "Waiting for next reminder");
+                (reminder - now).to_std().unwrap_or_default()
+            }
+            None => wait,
+        };
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("Daemon stopped");
+                return Ok(());
+            }
+        }
+
+        if let Some(reminder) = next_reminder {
+            match remind_once(bambo_processor, config, &DateRange::month_until(reminder.date())).await {
+                Ok(outcome) => tracing::info!(%reminder, %outcome, "Reminder done"),
+                Err(error) => tracing::error!(%reminder, %error, "Reminder failed"),
+            }
+            state.last_reminder = Some(reminder.format(STATE_TIME_FORMAT).to_string());
+            if let Err(e) = state.save(state_file) {
+                tracing::error!(error = %e, "Error while saving daemon state");
+            }
+            continue;
+        }
+
+        let (run, filled_days) = fill_once(bambo_processor, &DateRange::month_until(next_run.date())).await;
+        if run.success {
+            tracing::info!(%next_run, outcome = %run.outcome, "Run done");
+        } else {
+            tracing::error!(%next_run, outcome = %run.outcome, "Run failed");
+        }
+        if run.added_entries > 0 {
+            let notification = Notification::Filled { days: filled_days, entries: run.added_entries };
+            if let Err(e) = send_notification(config, &notification).await {
+                tracing::error!(error = %e, "Error while sending notification");
+            }
+        }
+        let given_up = !run.success && gives_up(trigger, &next_run, &Local::now().naive_local(), &config.schedule);
+        if given_up {
+            tracing::error!(%next_run, "Giving up the run, a later trigger has passed");
+            let notification = Notification::RunFailed { days: vec![format_date(&next_run.date())], error: run.outcome.clone() };
+            if let Err(e) = send_notification(config, &notification).await {
+                tracing::error!(error = %e, "Error while sending notification");
+            }
+        }
+        failures = if run.success || given_up { 0 } else { failures + 1 };
+        state.record(run, &next_run, given_up);
+        if let Err(e) = state.save(state_file) {
+            tracing::error!(error = %e, "Error while saving daemon state");
+        }
+    }
+}
+
+/// A failed run is retried until the trigger fires again, later runs must not wait for it forever
+fn gives_up(trigger: &Trigger, fired_at: &NaiveDateTime, now: &NaiveDateTime, schedule: &WorkSchedule) -> bool {
+    trigger.next_after(*fired_at, schedule).is_none_or(|next_run| next_run <= *now)
+}
+
+/// One minute after the first failure, doubling up to an hour
+fn retry_delay(failures: u32) -> std::time::Duration {
+    std::time::Duration::from_secs((60 * 2u64.saturating_pow(failures.saturating_sub(1))).min(3600))
+}
+
+/// Returns the run record and days entries were planned for
+async fn fill_once(bambo_processor: &BambooProcessor<'_>, range: &DateRange) -> (RunRecord, Vec<String>) {
+    let started_at = Local::now().format(STATE_TIME_FORMAT).to_string();
+
+    let status = match bambo_processor.get_timesheet_status(range).await {
+        Ok(status) => status,
+        Err(e) => {
+            return (RunRecord { started_at, success: false, added_entries: 0, outcome: format!("Error while getting timesheet status: {e}") }, Vec::new());
+        }
+    };
+    if status.entry_plan.entries.is_empty() {
+        return (RunRecord { started_at, success: true, added_entries: 0, outcome: "Nothing to do.".to_string() }, Vec::new());
+    }
+
+    let planned = status.entry_plan.entries.len();
+    let days: Vec<String> = status.entry_plan.entries.iter().map(|entry| String::from(&entry.date)).unique().collect();
+    let run = match bambo_processor.submit_entries(&status.entry_plan.entries).await {
+        Ok(result) => RunRecord {
+            started_at,
+            success: result.added + result.skipped == planned,
+            added_entries: result.added,
+            outcome: format!(
+                "Added {} of {planned} entries for days {}, {} already in Bamboo",
+                result.added,
+                days.join(", "),
+                result.skipped
+            ),
+        },
+        Err(e) => RunRecord { started_at, success: false, added_entries: 0, outcome: format!("Error while adding entries: {e}") },
+    };
+    (run, days)
+}
+
+/// Sends missing days in the range to the notifiers, returns the outcome to log
+async fn remind_once(bambo_processor: &BambooProcessor<'_>, config: &Config, range: &DateRange) -> Result<String, String> {
+    let status = match bambo_processor.get_timesheet_status(range).await {
+        Ok(status) => status,
+        Err(e) => return Err(format!("Error while getting timesheet status for the reminder: {e}")),
+    };
+    let missing_days: Vec<String> = status.eligible_days.iter().sorted().cloned().collect();
+    if missing_days.is_empty() {
+        return Ok("No missing days to remind about.".to_string());
+    }
+    match send_notification(config, &Notification::MissingDays { days: missing_days.clone() }).await {
+        Ok(0) => Ok("No notifier configured, reminder not sent".to_string()),
+        Ok(_) => Ok(format!("Reminder about {} missing days sent", missing_days.len())),
+        Err(e) => Err(format!("Error while sending reminder: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, STATE_TIME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_state_keeps_last_runs() {
+        let mut state = DaemonState::default();
+        for i in 0..KEPT_RUNS + 5 {
+            let started_at = format!("2024-08-01T17:00:{:02}", i % 60);
+            state.record(
+                RunRecord { started_at: started_at.clone(), success: true, added_entries: i, outcome: "ok".to_string() },
+                &date_time(&started_at),
+                false,
+            );
+        }
+
+        assert_eq!(state.runs.len(), KEPT_RUNS);
+        assert_eq!(state.runs[0].added_entries, 5);
+        assert_eq!(state.last_run_time(), NaiveDateTime::parse_from_str("2024-08-01T17:00:44", STATE_TIME_FORMAT).ok());
+    }
+
+    #[test]
+    fn test_failed_run_keeps_last_run() {
+        let mut state = DaemonState::default();
+        let run = |success| RunRecord { started_at: "2024-09-02T08:00:00".to_string(), success, added_entries: 0, outcome: "".to_string() };
+
+        state.record(run(true), &date_time("2024-08-30T17:00:00"), false);
+        state.record(run(false), &date_time("2024-09-02T17:00:00"), false);
+
+        assert_eq!(state.last_run.as_deref(), Some("2024-08-30T17:00:00"));
+        assert_eq!(state.runs.len(), 2);
+
+        state.record(run(false), &date_time("2024-09-02T17:00:00"), true);
+        assert_eq!(state.last_run.as_deref(), Some("2024-09-02T17:00:00"));
+    }
+
+    #[test]
+    fn test_failed_run_given_up_after_next_trigger() {
+        let trigger = Trigger::parse("weekdays@17:00").unwrap();
+        let schedule = WorkSchedule::default();
+        let fired_at = date_time("2024-08-30T17:00:00");
+
+        // still retried over the weekend, given up once monday's run is due
+        assert!(!gives_up(&trigger, &fired_at, &date_time("2024-09-01T12:00:00"), &schedule));
+        assert!(gives_up(&trigger, &fired_at, &date_time("2024-09-02T17:00:00"), &schedule));
+
+        // catching up: after giving up the next run is the following trigger, not the failed one again
+        let mut state = DaemonState::default();
+        state.record(
+            RunRecord { started_at: "2024-09-02T17:00:00".to_string(), success: false, added_entries: 0, outcome: "".to_string() },
+            &fired_at,
+            true,
+        );
+        assert_eq!(trigger.next_after(state.last_run_time().unwrap(), &schedule), Some(date_time("2024-09-02T17:00:00")));
+    }
+
+    #[test]
+    fn test_next_reminder() {
+        let trigger = Trigger::parse("last-working-day@09:00").unwrap();
+        let schedule = WorkSchedule::default();
+        let state = |last_reminder: Option<&str>| DaemonState { last_reminder: last_reminder.map(String::from), ..Default::default() };
+
+        // missed this month, sent right away
+        assert_eq!(
+            state(Some("2024-07-31T09:00:00")).next_reminder(&trigger, date_time("2024-08-30T12:00:00"), &schedule),
+            Some(date_time("2024-08-30T09:00:00"))
+        );
+        // missed last month, skipped
+        assert_eq!(
+            state(Some("2024-06-28T09:00:00")).next_reminder(&trigger, date_time("2024-08-05T12:00:00"), &schedule),
+            Some(date_time("2024-08-30T09:00:00"))
+        );
+        assert_eq!(
+            state(None).next_reminder(&trigger, date_time("2024-08-30T12:00:00"), &schedule),
+            Some(date_time("2024-09-30T09:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1).as_secs(), 60);
+        assert_eq!(retry_delay(3).as_secs(), 240);
+        assert_eq!(retry_delay(20).as_secs(), 3600);
+    }
+}
@@ -0,0 +1,157 @@
+use itertools::Itertools;
+
+use crate::{
+    bambo_processor::{EmployeeReport, SubmitResult, TimesheetStatus},
+    days_calculator::{get_weekday, DateRange},
+};
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:50rem;margin:2rem auto;padding:0 1rem}\
+table{border-collapse:collapse}td,th{padding:.2rem .8rem;text-align:left;border-bottom:1px solid #ddd}\
+.missing{color:#b35900}.complete{color:#2e7d32}nav a{margin-right:1rem}";
+
+/// Month status with the fill button. The button submits the shown range together with the form token of the server.
+pub fn render_status(range: &DateRange, status: &TimesheetStatus, form_token: &str) -> String {
+    let mut body = format!("<h1>Timesheet {} - {}</h1>", range.start, range.end);
+    body.push_str(&day_list("Bank holidays", &status.bank_holidays));
+    body.push_str(&day_list("Vacation", &status.vacation_days));
+    body.push_str(&day_list("Out of office", &status.out_of_office_days));
+
+    if status.eligible_days.is_empty() {
+        body.push_str("<p class=\"complete\">Nothing to do, the timesheet is complete.</p>");
+        return page("Timesheet", &body);
+    }
+    body.push_str("<h2>Missing days</h2><table><tr><th>Day</th><th>Planned entries</th></tr>");
+    for day in status.eligible_days.iter().sorted() {
+        let entries = status.entry_plan.entries_for_day(day);
+        let hours = if entries.is_empty() {
+            "fully taken, skipped".to_string()
+        } else {
+            entries.iter().map(|entry| format!("{}-{}", entry.start, entry.end)).join(", ")
+        };
+        body.push_str(&format!("<tr><td class=\"missing\">{}</td><td>{}</td></tr>", with_weekday(day), escape(&hours)));
+    }
+    body.push_str("</table>");
+
+    if !status.entry_plan.conflicts.is_empty() {
+        body.push_str("<h2>Conflicts</h2><ul>");
+        for conflict in &status.entry_plan.conflicts {
+            body.push_str(&format!(
+                "<li>{} {}-{} overlaps {}</li>",
+                conflict.date,
+                conflict.planned_start.format("%H:%M"),
+                conflict.planned_end.format("%H:%M"),
+                escape(&conflict.busy.reason)
+            ));
+        }
+        body.push_str("</ul>");
+    }
+    if !status.entry_plan.entries.is_empty() {
+        body.push_str(&format!(
+            "<form method=\"post\" action=\"/fill\"><input type=\"hidden\" name=\"from\" value=\"{}\">\
+<input type=\"hidden\" name=\"to\" value=\"{}\"><input type=\"hidden\" name=\"token\" value=\"{}\">\
+<button type=\"submit\">Fill {} entries</button></form>",
+            range.start,
+            range.end,
+            escape(form_token),
+            status.entry_plan.entries.len()
+        ));
+    }
+    page("Timesheet", &body)
+}
+
+pub fn render_report(range: &DateRange, reports: &[EmployeeReport]) -> String {
+    let mut body = format!("<h1>Team report {} - {}</h1>", range.start, range.end);
+    if reports.is_empty() {
+        body.push_str("<p>No team members. Start the server with --team=&lt;ID,ID,...&gt; or env variable TEAM_EMPLOYEE_IDS.</p>");
+        return page("Team report", &body);
+    }
+    body.push_str("<table><tr><th>Employee</th><th>Missing days</th></tr>");
+    for report in reports {
+        let missing = if report.missing_days.is_empty() {
+            "<span class=\"complete\">complete</span>".to_string()
+        } else {
+            report.missing_days.iter().sorted().map(|day| with_weekday(day)).join("<br>")
+        };
+        body.push_str(&format!("<tr><td>{} (#{})</td><td class=\"missing\">{missing}</td></tr>", escape(&report.name), report.employee_id));
+    }
+    body.push_str("</table>");
+    page("Team report", &body)
+}
+
+pub fn render_fill_result(result: &SubmitResult) -> String {
+    let body = format!(
+        "<h1>Timesheet filled</h1><p>Added {} entries, {} were already in Bamboo.</p><p><a href=\"/\">Back to the month</a></p>",
+        result.added, result.skipped
+    );
+    page("Timesheet filled", &body)
+}
+
+pub fn render_error(message: &str) -> String {
+    page("Error", &format!("<h1>Error</h1><p>{}</p><p><a href=\"/\">Back to the month</a></p>", escape(message)))
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{title} - Bamboo bot</title><style>{STYLE}</style></head>\
+<body><nav><a href=\"/\">Month</a><a href=\"/report\">Team report</a></nav>{body}</body></html>"
+    )
+}
+
+fn day_list(title: &str, days: &[String]) -> String {
+    if days.is_empty() {
+        return String::new();
+    }
+    format!("<h2>{title}</h2><p>{}</p>", days.iter().sorted().map(|day| with_weekday(day)).join(", "))
+}
+
+fn with_weekday(day: &str) -> String {
+    format!("{day} ({})", get_weekday(day).unwrap_or_default())
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{entry_planner::plan_entries, work_schedule::WorkSchedule};
+
+    use super::*;
+
+    #[test]
+    fn test_render_status() {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-16")).unwrap();
+        let eligible_days = vec!["2024-08-13".to_string()];
+        let status = TimesheetStatus {
+            bank_holidays: vec!["2024-08-15".to_string()],
+            vacation_days: vec![],
+            out_of_office_days: vec![],
+            entry_plan: plan_entries(12, &eligible_days, &[], &WorkSchedule::default()),
+            eligible_days,
+            timesheet_entries: vec![],
+        };
+
+        let html = render_status(&range, &status, "token");
+
+        assert!(html.contains("<h2>Bank holidays</h2><p>2024-08-15 (Thursday)</p>"));
+        assert!(html.contains("<td class=\"missing\">2024-08-13 (Tuesday)</td><td>08:00-16:00</td>"));
+        assert!(html.contains("<input type=\"hidden\" name=\"from\" value=\"2024-08-12\">"));
+        assert!(html.contains("<input type=\"hidden\" name=\"token\" value=\"token\">"));
+        assert!(html.contains("<button type=\"submit\">Fill 1 entries</button>"));
+        assert!(!html.contains("Vacation"));
+    }
+
+    #[test]
+    fn test_render_report_escapes_names() {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-16")).unwrap();
+        let reports = vec![EmployeeReport { employee_id: 12, name: "<Anna>".to_string(), missing_days: vec![] }];
+
+        let html = render_report(&range, &reports);
+
+        assert!(html.contains("<td>&lt;Anna&gt; (#12)</td><td class=\"missing\"><span class=\"complete\">complete</span></td>"));
+    }
+}
@@ -1,5 +1,74 @@
-use chrono::{DateTime, Datelike, Local, NaiveDate, Weekday};
+use chrono::{DateTime, Datelike, Local, NaiveDate};
 
+use crate::work_schedule::WorkSchedule;
+
+/// Inclusive range of days
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Result<Self, String> {
+        if start > end {
+            return Err(format!("Range start {start} is after range end {end}"));
+        }
+        Ok(DateRange { start, end })
+    }
+
+    /// From the first day of the month until today
+    pub fn this_month() -> Self {
+        DateRange {
+            start: get_first_day_of_the_month().date_naive(),
+            end: get_today().date_naive(),
+        }
+    }
+
+    /// From the first day of the month of `day` until `day`
+    pub fn month_until(day: NaiveDate) -> Self {
+        DateRange {
+            start: day.with_day(1).unwrap(),
+            end: day,
+        }
+    }
+
+    /// From today for given number of days
+    pub fn next_days(days: u64) -> Self {
+        let today = get_today().date_naive();
+        DateRange {
+            start: today,
+            end: today + chrono::Days::new(days.saturating_sub(1)),
+        }
+    }
+
+    /// Parses optional "YYYY-MM-DD" bounds, missing ones are taken from `this_month`
+    pub fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self, String> {
+        DateRange::parse_or(from, to, DateRange::this_month())
+    }
+
+    /// Parses optional "YYYY-MM-DD" bounds, missing ones are taken from `default`
+    pub fn parse_or(from: Option<&str>, to: Option<&str>, default: DateRange) -> Result<Self, String> {
+        let start = from.map(parse_date).transpose()?.unwrap_or(default.start);
+        let end = to.map(parse_date).transpose()?.unwrap_or(default.end);
+        DateRange::new(start, end)
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let end = self.end;
+        self.start.iter_days().take_while(move |day| *day <= end)
+    }
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| format!("Invalid date {value}, expected YYYY-MM-DD: {e}"))
+}
+
+pub fn format_date(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[allow(clippy::ptr_arg)]
 pub fn get_eligible_days_this_month(
     working_days: &Vec<String>, 
     vacation_days: &Vec<String>,
@@ -28,16 +97,12 @@ pub fn get_first_day_of_the_month() -> DateTime<Local> {
     today.with_day(1).unwrap()
 }
 
-pub fn get_working_days_this_month() -> Vec<String> {
-    let today = get_today();
-
+pub fn get_working_days(range: &DateRange, schedule: &WorkSchedule) -> Vec<String> {
     let mut working_days = Vec::new();
 
-    for i in 1..=today.day() {
-        let day = today.with_day(i).unwrap();
-        let day_of_week = day.naive_local().weekday();
-        if day_of_week != Weekday::Sat && day_of_week != Weekday::Sun {
-            working_days.push(day.format("%Y-%m-%d").to_string());
+    for day in range.days() {
+        if schedule.is_working_day(&day) {
+            working_days.push(format_date(&day));
         }
     }
     working_days
@@ -58,9 +123,10 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_get_working_days_this_month() {
         let today = Local::now().format("%Y-%m-%d").to_string();
-        let working_days = get_working_days_this_month();
+        let working_days = get_working_days(&DateRange::this_month(), &WorkSchedule::default());
         for day in &working_days {
             dbg!("{}", day);
         }
@@ -94,6 +160,17 @@ mod tests {
         assert_eq!(expected_days, eligible_days);
     }
 
+    #[test]
+    fn test_date_range_parse() {
+        let range = DateRange::parse(Some("2024-08-01"), Some("2024-08-31")).unwrap();
+
+        assert_eq!(range.days().count(), 31);
+        assert_eq!(get_working_days(&range, &WorkSchedule::default()).len(), 22);
+        assert_eq!(get_working_days(&range, &WorkSchedule::parse("mon-thu", 0.8, "08:00").unwrap()).len(), 17);
+        assert!(DateRange::parse(Some("2024-08-31"), Some("2024-08-01")).is_err());
+        assert!(DateRange::parse(Some("31.08.2024"), None).is_err());
+    }
+
     #[test]
     fn test_get_weekday() {
         let day = "2024-09-23";
@@ -0,0 +1,121 @@
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+use crate::{notification::Notification, secret::Secret};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<Secret>,
+    pub from: String,
+    pub to: String,
+    /// STARTTLS, disable only for local SMTP servers
+    pub tls: bool,
+}
+
+pub struct EmailNotifier<'a> {
+    smtp: &'a SmtpConfig,
+}
+
+impl<'a> EmailNotifier<'a> {
+    pub fn new(smtp: &'a SmtpConfig) -> Self {
+        EmailNotifier { smtp }
+    }
+
+    pub async fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        let mut transport = if self.smtp.tls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.smtp.host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.smtp.host)
+        }
+        .port(self.smtp.port);
+        if let (Some(username), Some(password)) = (&self.smtp.username, &self.smtp.password) {
+            transport = transport.credentials(Credentials::new(username.clone(), password.expose().to_string()));
+        }
+
+        let email = Message::builder()
+            .from(self.smtp.from.parse()?)
+            .to(self.smtp.to.parse()?)
+            .subject(notification.subject())
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.text())?;
+
+        transport.build().send(email).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Accepts a single message and returns everything the client sent
+    async fn smtp_sink(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let mut received = String::new();
+        let mut in_data = false;
+
+        writer.write_all(b"220 localhost ESMTP sink\r\n").await.unwrap();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            received.push_str(&line);
+            let reply: &[u8] = if in_data {
+                if line != ".\r\n" {
+                    continue;
+                }
+                in_data = false;
+                b"250 OK\r\n"
+            } else if line.starts_with("EHLO") {
+                b"250 localhost\r\n"
+            } else if line.starts_with("DATA") {
+                in_data = true;
+                b"354 End data with <CR><LF>.<CR><LF>\r\n"
+            } else if line.starts_with("QUIT") {
+                writer.write_all(b"221 Bye\r\n").await.unwrap();
+                break;
+            } else {
+                b"250 OK\r\n"
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_send_email_to_local_sink() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink = tokio::spawn(smtp_sink(listener));
+        let smtp = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: None,
+            from: "bot@example.com".to_string(),
+            to: "employee@example.com".to_string(),
+            tls: false,
+        };
+        let notification = Notification::MissingDays { days: vec!["2024-09-23".to_string()] };
+
+        EmailNotifier::new(&smtp).send(&notification).await.unwrap();
+        let received = sink.await.unwrap();
+
+        assert!(received.contains("RCPT TO:<employee@example.com>"));
+        assert!(received.contains("Subject: Timesheet: you are missing 1 days this month"));
+        assert!(received.contains("2024-09-23 (Monday)"));
+    }
+}
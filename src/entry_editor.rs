@@ -0,0 +1,108 @@
+use chrono::{Duration, NaiveTime};
+
+use crate::{
+    entry_planner::parse_time,
+    model::{add_timesheet_entry::add_entry_request::AddTimesheetEntry, get_timesheet_entries::timesheet_entry::TimsheetEntry},
+};
+
+/// Parses "[+-]HH:MM", e.g. "+01:00" or "-00:30"
+pub fn parse_shift(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("Invalid shift {value}, expected e.g. +01:00 or -00:30");
+    let (sign, time) = match value.trim() {
+        value if value.starts_with('-') => (-1, &value[1..]),
+        value => (1, value.trim_start_matches('+')),
+    };
+    let (hours, minutes) = time.split_once(':').ok_or_else(invalid)?;
+    let hours = hours.parse::<i64>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<i64>().map_err(|_| invalid())?;
+    if minutes >= 60 {
+        return Err(invalid());
+    }
+    Ok(Duration::minutes(sign * (hours * 60 + minutes)))
+}
+
+/// Clock entries of the day moved by `shift`, ready to be stored as updates. Hour entries have no times and are left out.
+pub fn shift_entries(entries: &[TimsheetEntry], day: &str, shift: Duration) -> Result<Vec<AddTimesheetEntry>, String> {
+    entries
+        .iter()
+        .filter(|entry| entry.date == day)
+        .filter_map(|entry| {
+            let start = parse_time(entry.start.as_deref()?)?;
+            let end = parse_time(entry.end.as_deref()?)?;
+            Some((entry, start, end))
+        })
+        .map(|(entry, start, end)| {
+            let shifted_start = shift_time(start, shift).ok_or_else(|| format!("Entry {} would start on another day", entry.id))?;
+            let shifted_end = shift_time(end, shift).ok_or_else(|| format!("Entry {} would end on another day", entry.id))?;
+            Ok(AddTimesheetEntry {
+                id: Some(entry.id),
+                employee_id: entry.employee_id,
+                date: String::from(&entry.date),
+                start: shifted_start.format("%H:%M").to_string(),
+                end: shifted_end.format("%H:%M").to_string(),
+                project_id: entry.project_info.as_ref().map(|info| info.project.id),
+                task_id: entry.project_info.as_ref().map(|info| info.task.id),
+                note: entry.note.clone(),
+            })
+        })
+        .collect()
+}
+
+fn shift_time(time: NaiveTime, shift: Duration) -> Option<NaiveTime> {
+    let (shifted, overflow) = time.overflowing_add_signed(shift);
+    (overflow == 0).then_some(shifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, date: &str, start: Option<&str>, end: Option<&str>) -> TimsheetEntry {
+        TimsheetEntry {
+            id,
+            employee_id: 12,
+            r#type: "clock".to_string(),
+            date: date.to_string(),
+            start: start.map(String::from),
+            end: end.map(String::from),
+            timezone: "Europe/Warsaw".to_string(),
+            hours: 0.0,
+            note: Some("Release".to_string()),
+            project_info: None,
+            approved_at: None,
+            approved: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_shift() {
+        assert_eq!(parse_shift("+01:00").unwrap(), Duration::hours(1));
+        assert_eq!(parse_shift("-00:30").unwrap(), Duration::minutes(-30));
+        assert_eq!(parse_shift("02:15").unwrap(), Duration::minutes(135));
+        assert!(parse_shift("1h").is_err());
+        assert!(parse_shift("+00:75").is_err());
+    }
+
+    #[test]
+    fn test_shift_entries() {
+        let entries = vec![
+            entry(1, "2024-08-19", Some("2024-08-19T08:00:00+02:00"), Some("2024-08-19T12:00:00+02:00")),
+            entry(2, "2024-08-19", None, None),
+            entry(3, "2024-08-20", Some("08:00"), Some("16:00")),
+        ];
+
+        let shifted = shift_entries(&entries, "2024-08-19", Duration::minutes(-30)).unwrap();
+
+        assert_eq!(shifted.len(), 1);
+        assert_eq!(shifted[0].id, Some(1));
+        assert_eq!((shifted[0].start.as_str(), shifted[0].end.as_str()), ("07:30", "11:30"));
+        assert_eq!(shifted[0].note.as_deref(), Some("Release"));
+    }
+
+    #[test]
+    fn test_shift_over_midnight() {
+        let entries = vec![entry(1, "2024-08-19", Some("20:00"), Some("23:00"))];
+
+        assert!(shift_entries(&entries, "2024-08-19", Duration::hours(2)).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, NaiveTime, TimeDelta};
+use itertools::Itertools;
+
+use crate::{
+    model::{
+        add_timesheet_entry::add_entry_request::AddTimesheetEntry,
+        get_timesheet_entries::timesheet_entry::TimsheetEntry,
+    },
+    work_schedule::WorkSchedule,
+};
+
+/// Part of a day which is already taken and must not be covered by a new entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusyWindow {
+    pub date: String,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub date: String,
+    pub planned_start: NaiveTime,
+    pub planned_end: NaiveTime,
+    pub busy: BusyWindow,
+}
+
+#[derive(Debug, Default)]
+pub struct EntryPlan {
+    pub entries: Vec<AddTimesheetEntry>,
+    pub conflicts: Vec<Conflict>,
+    pub skipped_days: Vec<String>,
+    /// Busy windows the entries were planned around, edited entries must keep out of them too
+    pub busy_windows: Vec<BusyWindow>,
+}
+
+impl EntryPlan {
+    pub fn entries_for_day(&self, day: &str) -> Vec<AddTimesheetEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.date == day)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Plans entries covering working hours of the schedule for given days, cutting out every busy window.
+/// Days which end up without any free time are reported in `skipped_days`.
+pub fn plan_entries(employee_id: i32, days: &[String], busy_windows: &[BusyWindow], schedule: &WorkSchedule) -> EntryPlan {
+    let (day_start, day_end) = schedule.working_hours();
+    let mut plan = EntryPlan { busy_windows: busy_windows.to_vec(), ..Default::default() };
+
+    for day in days {
+        let mut free = vec![(day_start, day_end)];
+
+        for busy in busy_windows.iter().filter(|busy| &busy.date == day) {
+            let mut remaining = Vec::new();
+            for (start, end) in free {
+                if busy.start >= end || busy.end <= start {
+                    remaining.push((start, end));
+                    continue;
+                }
+                plan.conflicts.push(Conflict {
+                    date: String::from(day),
+                    planned_start: start,
+                    planned_end: end,
+                    busy: busy.clone(),
+                });
+                if busy.start > start {
+                    remaining.push((start, busy.start));
+                }
+                if busy.end < end {
+                    remaining.push((busy.end, end));
+                }
+            }
+            free = remaining;
+        }
+
+        if free.is_empty() {
+            plan.skipped_days.push(String::from(day));
+        }
+        for (start, end) in free {
+            plan.entries.push(AddTimesheetEntry {
+                id: None,
+                employee_id,
+                date: String::from(day),
+                start: start.format("%H:%M").to_string(),
+                end: end.format("%H:%M").to_string(),
+                project_id: None,
+                task_id: None,
+                note: None,
+            });
+        }
+    }
+    plan
+}
+
+/// Days which are already done: clock entries add up to the daily hours, or there is an hour entry
+/// (those have no start/end, so nothing can be planned around them). Other days with entries are
+/// planned around the existing entries.
+pub fn filled_days(entries: &[TimsheetEntry], daily_hours: f32) -> Vec<String> {
+    let mut worked_minutes: HashMap<&str, i64> = HashMap::new();
+    let mut days = Vec::new();
+    for entry in entries {
+        let (Some(start), Some(end)) = (entry.start.as_deref().and_then(parse_time), entry.end.as_deref().and_then(parse_time)) else {
+            if entry.start.is_none() && !days.contains(&entry.date) {
+                days.push(String::from(&entry.date));
+            }
+            continue;
+        };
+        *worked_minutes.entry(&entry.date).or_default() += (end - start).num_minutes();
+    }
+    for (day, minutes) in worked_minutes {
+        if minutes as f32 >= daily_hours * 60.0 && !days.iter().any(|filled| filled == day) {
+            days.push(day.to_string());
+        }
+    }
+    days
+}
+
+/// Clock entries already stored in Bamboo. Hour entries have no start/end so they can't collide.
+pub fn busy_windows_from_entries(entries: &[TimsheetEntry]) -> Vec<BusyWindow> {
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let start = parse_time(entry.start.as_deref()?)?;
+            let end = match entry.end.as_deref() {
+                Some(end) => parse_time(end)?,
+                None => end_of_day,
+            };
+            Some(BusyWindow {
+                date: String::from(&entry.date),
+                start,
+                end,
+                reason: format!(
+                    "existing entry {}-{}",
+                    start.format("%H:%M"),
+                    end.format("%H:%M")
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Planned entries which are not in Bamboo yet. An entry is dropped when it overlaps a stored clock entry
+/// or its day already has an hour entry, so submitting the same plan twice doesn't add anything.
+pub fn remove_existing(entries: &[AddTimesheetEntry], remote: &[TimsheetEntry]) -> Vec<AddTimesheetEntry> {
+    let busy = busy_windows_from_entries(remote);
+    entries
+        .iter()
+        .filter(|entry| {
+            let has_hour_entry = remote.iter().any(|stored| stored.date == entry.date && stored.start.is_none());
+            let (Some(start), Some(end)) = (parse_time(&entry.start), parse_time(&entry.end)) else {
+                return !has_hour_entry;
+            };
+            !has_hour_entry
+                && !busy
+                    .iter()
+                    .any(|window| window.date == entry.date && window.start < end && window.end > start)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Fixes of entries already stored in Bamboo
+#[derive(Debug, Default, PartialEq)]
+pub struct RemoteChanges {
+    pub update: Vec<AddTimesheetEntry>,
+    pub delete: Vec<u32>,
+}
+
+/// Entries on days off (holidays, full day time off) and exact duplicates of another clock entry are deleted.
+/// Clock entries left open on days before `today` are closed at the end of working hours.
+/// Approved entries are never touched.
+pub fn plan_remote_changes(remote: &[TimsheetEntry], days_off: &[String], today: &str, schedule: &WorkSchedule) -> RemoteChanges {
+    let (_, day_end) = schedule.working_hours();
+    let mut changes = RemoteChanges::default();
+    let mut kept: Vec<(&str, NaiveTime, Option<NaiveTime>)> = Vec::new();
+
+    for entry in remote.iter().sorted_by_key(|entry| (entry.approved != Some(true), entry.id)) {
+        let approved = entry.approved == Some(true);
+        if days_off.contains(&entry.date) && !approved {
+            changes.delete.push(entry.id);
+            continue;
+        }
+        let Some(start) = entry.start.as_deref().and_then(parse_time) else {
+            continue;
+        };
+        let end = entry.end.as_deref().and_then(parse_time);
+        if kept.contains(&(entry.date.as_str(), start, end)) && !approved {
+            changes.delete.push(entry.id);
+            continue;
+        }
+        kept.push((&entry.date, start, end));
+        if !approved && end.is_none() && entry.date.as_str() < today && start < day_end {
+            changes.update.push(AddTimesheetEntry {
+                id: Some(entry.id),
+                employee_id: entry.employee_id,
+                date: String::from(&entry.date),
+                start: start.format("%H:%M").to_string(),
+                end: day_end.format("%H:%M").to_string(),
+                project_id: entry.project_info.as_ref().map(|info| info.project.id),
+                task_id: entry.project_info.as_ref().map(|info| info.task.id),
+                note: entry.note.clone(),
+            });
+        }
+    }
+    changes
+}
+
+/// Bamboo doesn't say which part of the day partial time off takes,
+/// so it is assumed to be the end of the working day.
+pub fn busy_windows_from_time_off(partial_days: &[(String, f32)], schedule: &WorkSchedule) -> Vec<BusyWindow> {
+    let (day_start, day_end) = schedule.working_hours();
+
+    partial_days
+        .iter()
+        .filter(|(_, hours)| *hours > 0.0)
+        .map(|(date, hours)| {
+            let minutes = (hours.min(schedule.daily_hours()) * 60.0).round() as i64;
+            BusyWindow {
+                date: String::from(date),
+                start: (day_end - TimeDelta::minutes(minutes)).max(day_start),
+                end: day_end,
+                reason: format!("{hours}h time off"),
+            }
+        })
+        .collect()
+}
+
+/// Accepts "HH:MM", "HH:MM:SS" and full timestamps as returned by the timesheet endpoint.
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.time());
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Some(date_time.time());
+    }
+    NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(date: &str, start: &str, end: &str) -> BusyWindow {
+        BusyWindow {
+            date: date.to_string(),
+            start: parse_time(start).unwrap(),
+            end: parse_time(end).unwrap(),
+            reason: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_entries_without_conflicts() {
+        let days = vec!["2024-08-01".to_string(), "2024-08-02".to_string()];
+        let plan = plan_entries(1, &days, &[window("2024-08-05", "10:00", "11:00")], &WorkSchedule::default());
+
+        assert_eq!(plan.entries.len(), 2);
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.entries[0].start, "08:00");
+        assert_eq!(plan.entries[0].end, "16:00");
+    }
+
+    #[test]
+    fn test_plan_entries_splits_around_existing_entry() {
+        let days = vec!["2024-08-01".to_string()];
+        let plan = plan_entries(1, &days, &[window("2024-08-01", "10:00", "11:00")], &WorkSchedule::default());
+
+        let hours: Vec<(String, String)> = plan
+            .entries
+            .iter()
+            .map(|entry| (entry.start.clone(), entry.end.clone()))
+            .collect();
+        assert_eq!(
+            hours,
+            vec![
+                ("08:00".to_string(), "10:00".to_string()),
+                ("11:00".to_string(), "16:00".to_string())
+            ]
+        );
+        assert_eq!(plan.conflicts.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_entries_skips_fully_covered_day() {
+        let days = vec!["2024-08-01".to_string()];
+        let schedule = WorkSchedule::default();
+        let plan = plan_entries(1, &days, &busy_windows_from_time_off(&[("2024-08-01".to_string(), 8.0)], &schedule), &schedule);
+
+        assert!(plan.entries.is_empty());
+        assert_eq!(plan.skipped_days, days);
+    }
+
+    #[test]
+    fn test_busy_windows_from_time_off_takes_end_of_day() {
+        let windows = busy_windows_from_time_off(&[("2024-08-01".to_string(), 4.0)], &WorkSchedule::default());
+
+        assert_eq!(windows[0].start, parse_time("12:00").unwrap());
+        assert_eq!(windows[0].end, parse_time("16:00").unwrap());
+    }
+
+    #[test]
+    fn test_plan_entries_follows_schedule() {
+        let days = vec!["2024-08-01".to_string()];
+        let schedule = WorkSchedule::parse("mon-fri", 0.8, "09:00").unwrap();
+        let plan = plan_entries(1, &days, &[], &schedule);
+
+        assert_eq!(plan.entries[0].start, "09:00");
+        assert_eq!(plan.entries[0].end, "15:24");
+    }
+
+    #[test]
+    fn test_remove_existing() {
+        let days = vec!["2024-08-01".to_string(), "2024-08-02".to_string(), "2024-08-05".to_string()];
+        let plan = plan_entries(1, &days, &[], &WorkSchedule::default());
+        let stored = |id: u32, date: &str, start: Option<&str>, end: Option<&str>| TimsheetEntry {
+            id,
+            employee_id: 1,
+            r#type: "clock".to_string(),
+            date: date.to_string(),
+            start: start.map(String::from),
+            end: end.map(String::from),
+            timezone: "Europe/Warsaw".to_string(),
+            hours: 0.0,
+            note: None,
+            project_info: None,
+            approved_at: None,
+            approved: None,
+        };
+        let remote = vec![
+            stored(1, "2024-08-01", Some("2024-08-01T08:00:00+02:00"), Some("2024-08-01T16:00:00+02:00")),
+            stored(2, "2024-08-02", None, None),
+            stored(3, "2024-08-05", Some("16:00"), Some("18:00")),
+        ];
+
+        let remaining = remove_existing(&plan.entries, &remote);
+
+        assert_eq!(remaining, plan.entries_for_day("2024-08-05"));
+    }
+
+    #[test]
+    fn test_plan_remote_changes() {
+        let stored = |id: u32, date: &str, start: Option<&str>, end: Option<&str>, approved: bool| TimsheetEntry {
+            id,
+            employee_id: 1,
+            r#type: "clock".to_string(),
+            date: date.to_string(),
+            start: start.map(String::from),
+            end: end.map(String::from),
+            timezone: "Europe/Warsaw".to_string(),
+            hours: 0.0,
+            note: None,
+            project_info: None,
+            approved_at: None,
+            approved: Some(approved),
+        };
+        let remote = vec![
+            stored(1, "2024-08-15", Some("08:00"), Some("16:00"), false),
+            stored(2, "2024-08-16", Some("08:00"), Some("16:00"), false),
+            stored(3, "2024-08-16", Some("08:00"), Some("16:00"), true),
+            stored(4, "2024-08-19", Some("09:30"), None, false),
+            stored(5, "2024-08-20", Some("09:30"), None, false),
+            stored(6, "2024-08-15", Some("10:00"), Some("12:00"), true),
+        ];
+
+        let changes = plan_remote_changes(&remote, &["2024-08-15".to_string()], "2024-08-20", &WorkSchedule::default());
+
+        assert_eq!(changes.delete, vec![1, 2]);
+        assert_eq!(changes.update.len(), 1);
+        assert_eq!(changes.update[0].id, Some(4));
+        assert_eq!((changes.update[0].start.as_str(), changes.update[0].end.as_str()), ("09:30", "16:00"));
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("2024-08-01T10:30:00+02:00"), parse_time("10:30"));
+        assert_eq!(parse_time("2024-08-01T10:30:00"), parse_time("10:30:00"));
+        assert_eq!(parse_time("not a time"), None);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Mutex,
+};
+
+use reqwest::{Method, Response, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields with names, contact details or free text of people
+const PERSONAL_FIELDS: [&str; 10] = [
+    "displayName",
+    "firstName",
+    "lastName",
+    "preferredName",
+    "supervisor",
+    "email",
+    "workEmail",
+    "mobilePhone",
+    "workPhone",
+    "photoUrl",
+];
+const FREE_TEXT_FIELDS: [&str; 2] = ["note", "notes"];
+
+/// Recorded response of a BambooHR endpoint. Every method and path below `/v1/` has a file with a list of responses,
+/// one per query. When replaying, the response with the same query is used. Otherwise (e.g. dates relative to today)
+/// responses are used in the order they were recorded, so the company name and dates don't matter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+    pub status: u16,
+    pub body: Value,
+}
+
+/// Recording or replaying fixtures, remembers files recorded or calls replayed so far in this run by fixture file name
+pub enum FixtureMode {
+    Record { dir: String, pseudonyms: Mutex<HashMap<String, String>>, recorded: Mutex<HashSet<String>> },
+    Replay { dir: String, calls: Mutex<HashMap<String, usize>> },
+}
+
+impl FixtureMode {
+    pub fn record(dir: &str) -> Self {
+        FixtureMode::Record { dir: dir.to_string(), pseudonyms: Default::default(), recorded: Default::default() }
+    }
+
+    pub fn replay(dir: &str) -> Self {
+        FixtureMode::Replay { dir: dir.to_string(), calls: Default::default() }
+    }
+}
+
+impl Fixture {
+    pub fn new(method: &Method, url: &Url, status: u16, body: &str) -> Self {
+        let body = match body {
+            "" => Value::Null,
+            body => serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string())),
+        };
+        Fixture {
+            method: method.to_string(),
+            path: api_path(url),
+            query: url.query().map(String::from),
+            status,
+            body,
+        }
+    }
+
+    /// Response recorded with the same query, or the `call`-th recorded one (the last one when there are fewer)
+    pub fn load(dir: &str, method: &Method, url: &Url, call: usize) -> Result<Fixture, Box<dyn std::error::Error>> {
+        let path = Path::new(dir).join(fixture_name(method.as_str(), &api_path(url)));
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("No fixture for {method} {} in {}: {e}", api_path(url), path.display()))?;
+        let mut fixtures: Vec<Fixture> = serde_json::from_str(&content)?;
+        if fixtures.is_empty() {
+            return Err(format!("No fixture for {method} {} in {}", api_path(url), path.display()).into());
+        }
+        let position = fixtures
+            .iter()
+            .position(|fixture| fixture.query.as_deref() == url.query())
+            .unwrap_or(call.min(fixtures.len() - 1));
+        Ok(fixtures.swap_remove(position))
+    }
+
+    /// Adds the response to the endpoint's file, replacing a response with the same query.
+    /// Responses from earlier recordings are dropped unless `keep_recorded` is set.
+    pub fn save(&self, dir: &str, keep_recorded: bool) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let path = Path::new(dir).join(fixture_name(&self.method, &self.path));
+        let mut fixtures: Vec<Fixture> = match fs::read_to_string(&path) {
+            Ok(content) if keep_recorded => serde_json::from_str(&content)?,
+            _ => Vec::new(),
+        };
+        match fixtures.iter_mut().find(|fixture| fixture.query == self.query) {
+            Some(fixture) => *fixture = self.clone(),
+            None => fixtures.push(self.clone()),
+        }
+        fs::write(path, serde_json::to_string_pretty(&fixtures)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn into_response(self) -> Result<Response, Box<dyn std::error::Error>> {
+        let body = match self.body {
+            Value::Null => String::new(),
+            Value::String(text) => text,
+            body => body.to_string(),
+        };
+        let response = http::Response::builder()
+            .status(self.status)
+            .header("Content-Type", "application/json")
+            .body(body)?;
+        Ok(Response::from(response))
+    }
+}
+
+/// `get_time_off_whos_out.json` for `GET /api/gateway.php/{company}/v1/time_off/whos_out`
+pub fn fixture_name(method: &str, path: &str) -> String {
+    format!("{}_{}.json", method.to_lowercase(), path.replace('/', "_"))
+}
+
+fn api_path(url: &Url) -> String {
+    let path = url.path();
+    match path.find("/v1/") {
+        Some(index) => path[index + 4..].to_string(),
+        None => path.trim_start_matches('/').to_string(),
+    }
+}
+
+/// Replaces names, contact details and notes of people with stable pseudonyms, so the same person
+/// has the same name in every recorded fixture. Names of holidays and time off types are kept.
+pub fn anonymize(value: &mut Value, pseudonyms: &mut HashMap<String, String>) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|item| anonymize(item, pseudonyms)),
+        Value::Object(object) => {
+            let is_employee = object.contains_key("employeeId");
+            for (key, field) in object.iter_mut() {
+                match (key.as_str(), field) {
+                    (key, Value::String(text)) if PERSONAL_FIELDS.contains(&key) || (key == "name" && is_employee) => {
+                        if key.ends_with("mail") {
+                            *text = format!("{}@example.com", pseudonym(text, pseudonyms).to_lowercase().replace(' ', "."));
+                        } else if key.ends_with("Phone") || key == "photoUrl" {
+                            text.clear();
+                        } else {
+                            *text = pseudonym(text, pseudonyms);
+                        }
+                    }
+                    (key, field) if FREE_TEXT_FIELDS.contains(&key) => redact_text(field),
+                    (_, field) => anonymize(field, pseudonyms),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn pseudonym(name: &str, pseudonyms: &mut HashMap<String, String>) -> String {
+    let next = pseudonyms.len() + 1;
+    pseudonyms.entry(name.to_string()).or_insert_with(|| format!("Person {next}")).clone()
+}
+
+fn redact_text(value: &mut Value) {
+    match value {
+        Value::String(text) if !text.is_empty() => *text = "[redacted]".to_string(),
+        Value::Array(items) => items.iter_mut().for_each(redact_text),
+        Value::Object(object) => object.values_mut().for_each(redact_text),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_anonymize() {
+        let mut pseudonyms = HashMap::new();
+        let mut whos_out = json!([
+            { "id": 1, "type": "timeOff", "employeeId": 12, "name": "Anna Nowak", "start": "2024-08-19", "end": "2024-08-20" },
+            { "id": 2, "type": "holiday", "name": "Assumption Day", "start": "2024-08-15", "end": "2024-08-15" },
+        ]);
+        let mut directory = json!({ "employees": [
+            { "id": "12", "displayName": "Anna Nowak", "workEmail": "anna@acme.com", "supervisor": "Jan Kowalski", "department": "IT" },
+        ]});
+        let mut request = json!({ "employeeId": "12", "name": "Anna Nowak", "type": { "name": "Vacation" }, "notes": { "employee": "Wedding", "manager": "" } });
+
+        anonymize(&mut whos_out, &mut pseudonyms);
+        anonymize(&mut directory, &mut pseudonyms);
+        anonymize(&mut request, &mut pseudonyms);
+
+        assert_eq!(whos_out[0]["name"], "Person 1");
+        assert_eq!(whos_out[1]["name"], "Assumption Day");
+        assert_eq!(
+            directory,
+            json!({ "employees": [
+                { "id": "12", "displayName": "Person 1", "workEmail": "person.3@example.com", "supervisor": "Person 2", "department": "IT" },
+            ]})
+        );
+        assert_eq!(request["name"], "Person 1");
+        assert_eq!(request["type"]["name"], "Vacation");
+        assert_eq!(request["notes"], json!({ "employee": "[redacted]", "manager": "" }));
+    }
+
+    #[test]
+    fn test_fixture_name() {
+        let url = Url::parse("https://api.bamboohr.com/api/gateway.php/acme/v1/employees/12/time_off/calculator?end=2024-12-31").unwrap();
+        let fixture = Fixture::new(&Method::GET, &url, 200, "[]");
+
+        assert_eq!(fixture.path, "employees/12/time_off/calculator");
+        assert_eq!(fixture.query.as_deref(), Some("end=2024-12-31"));
+        assert_eq!(fixture_name(&fixture.method, &fixture.path), "get_employees_12_time_off_calculator.json");
+    }
+
+    #[test]
+    fn test_fixtures_matched_by_query() {
+        let dir = std::env::temp_dir().join(format!("bamboo-bot-fixtures-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let url = |query: &str| Url::parse(&format!("https://api.bamboohr.com/api/gateway.php/acme/v1/employees/12/time_off/calculator?{query}")).unwrap();
+        Fixture::new(&Method::GET, &url("end=2024-08-20"), 200, "[1]").save(dir, false).unwrap();
+        Fixture::new(&Method::GET, &url("end=2024-12-31"), 200, "[2]").save(dir, true).unwrap();
+        Fixture::new(&Method::GET, &url("end=2024-08-20"), 200, "[3]").save(dir, true).unwrap();
+
+        let body = |query: &str, call: usize| Fixture::load(dir, &Method::GET, &url(query), call).unwrap().body;
+
+        assert_eq!(body("end=2024-12-31", 0), json!([2]));
+        assert_eq!(body("end=2024-08-20", 1), json!([3]));
+        assert_eq!(body("end=2025-01-15", 0), json!([3]));
+        assert_eq!(body("end=2025-12-31", 1), json!([2]));
+        assert_eq!(body("end=2025-12-31", 5), json!([2]));
+
+        Fixture::new(&Method::GET, &url("end=2024-08-21"), 200, "[4]").save(dir, false).unwrap();
+        assert_eq!(body("end=2024-12-31", 1), json!([4]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
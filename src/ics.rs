@@ -0,0 +1,302 @@
+use std::{fs, path::Path};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+
+const OUT_OF_OFFICE_PHRASES: [&str; 6] = ["out of office", "holiday", "vacation", "time off", "urlop", "urlaub"];
+const OUT_OF_OFFICE_WORDS: [&str; 3] = ["ooo", "pto", "oof"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTime {
+    AllDay(NaiveDate),
+    Timed(NaiveDateTime, NaiveDateTime),
+}
+
+/// Event written by `write_calendar`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEvent {
+    pub uid: String,
+    pub summary: String,
+    pub time: EventTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub start: NaiveDate,
+    /// Exclusive, as DTEND in iCalendar
+    pub end: NaiveDate,
+    pub all_day: bool,
+    pub busy_status: Option<String>,
+    pub cancelled: bool,
+}
+
+impl CalendarEvent {
+    pub fn is_out_of_office(&self) -> bool {
+        if self.cancelled || !self.all_day {
+            return false;
+        }
+        if self.busy_status.as_deref() == Some("OOF") {
+            return true;
+        }
+        let summary = self.summary.to_lowercase();
+        OUT_OF_OFFICE_PHRASES.iter().any(|phrase| summary.contains(phrase))
+            || summary
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| OUT_OF_OFFICE_WORDS.contains(&word))
+    }
+
+    pub fn days(&self) -> Vec<NaiveDate> {
+        let mut days = Vec::new();
+        let mut day = self.start;
+        loop {
+            days.push(day);
+            day += TimeDelta::days(1);
+            if day >= self.end {
+                break;
+            }
+        }
+        days
+    }
+}
+
+/// Parses VEVENTs of an iCalendar file. Recurring events are read as a single occurrence.
+pub fn parse_events(content: &str) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut properties: Option<Vec<(String, String, String)>> = None;
+
+    for line in unfold_lines(content) {
+        let Some((name_and_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name_and_params
+            .split_once(';')
+            .unwrap_or((name_and_params, ""));
+        let name = name.to_uppercase();
+
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => properties = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(event) = properties.take().and_then(|properties| to_event(&properties)) {
+                    events.push(event);
+                }
+            }
+            _ => {
+                if let Some(properties) = properties.as_mut() {
+                    properties.push((name, params.to_uppercase(), value.to_string()));
+                }
+            }
+        }
+    }
+    events
+}
+
+/// All-day out of office days between `from` and `to` from an .ics file or a directory of them.
+pub fn get_out_of_office_days(path: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<String>, String> {
+    let path = Path::new(path);
+    let files = if path.is_dir() {
+        let entries = fs::read_dir(path).map_err(|e| format!("Error while reading directory {}: {e}", path.display()))?;
+        entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|file| file.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("ics")))
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut days = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file).map_err(|e| format!("Error while reading calendar {}: {e}", file.display()))?;
+        for event in parse_events(&content).into_iter().filter(CalendarEvent::is_out_of_office) {
+            for day in event.days().into_iter().filter(|day| (from..=to).contains(day)) {
+                let day = day.format("%Y-%m-%d").to_string();
+                if !days.contains(&day) {
+                    days.push(day);
+                }
+            }
+        }
+    }
+    days.sort();
+    Ok(days)
+}
+
+/// Renders events as an iCalendar file. Timed events use floating (local) time.
+pub fn write_calendar(events: &[ExportEvent], stamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//bamboo-bot//timesheet//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+        match event.time {
+            EventTime::AllDay(day) => {
+                lines.push(format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")));
+                lines.push(format!("DTEND;VALUE=DATE:{}", (day + TimeDelta::days(1)).format("%Y%m%d")));
+                lines.push("TRANSP:TRANSPARENT".to_string());
+            }
+            EventTime::Timed(start, end) => {
+                lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+                lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%S")));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect::<Vec<_>>().join("")
+}
+
+/// Lines longer than 75 octets are continued on the next line starting with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_length = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unfold_lines(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix(' ').or(line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.trim_end_matches('\r').to_string()),
+        }
+    }
+    lines
+}
+
+fn to_event(properties: &[(String, String, String)]) -> Option<CalendarEvent> {
+    let property = |name: &str| properties.iter().find(|(property, _, _)| property == name);
+
+    let (_, start_params, start) = property("DTSTART")?;
+    let all_day = start_params.contains("VALUE=DATE")
+        || start.len() == 8
+        || property("X-MICROSOFT-CDO-ALLDAYEVENT").is_some_and(|(_, _, value)| value.eq_ignore_ascii_case("TRUE"));
+    let start = parse_date(start)?;
+    let end = property("DTEND")
+        .and_then(|(_, _, end)| parse_date(end))
+        .filter(|end| *end > start)
+        .unwrap_or(start + TimeDelta::days(1));
+
+    Some(CalendarEvent {
+        summary: property("SUMMARY").map(|(_, _, summary)| unescape(summary)).unwrap_or_default(),
+        start,
+        end,
+        all_day,
+        busy_status: property("X-MICROSOFT-CDO-BUSYSTATUS").map(|(_, _, status)| status.to_uppercase()),
+        cancelled: property("STATUS").is_some_and(|(_, _, status)| status.eq_ignore_ascii_case("CANCELLED")),
+    })
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20240819\r
+DTEND;VALUE=DATE:20240821\r
+SUMMARY:OOO - family\r
+ trip\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART:20240822T090000Z\r
+DTEND:20240822T100000Z\r
+SUMMARY:Holiday planning meeting\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20240826\r
+SUMMARY:Team offsite\r
+X-MICROSOFT-CDO-BUSYSTATUS:OOF\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20240827\r
+SUMMARY:Zoom marathon\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn test_parse_events() {
+        let events = parse_events(CALENDAR);
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].summary, "OOO - familytrip");
+        assert!(events[0].all_day);
+        assert!(!events[1].all_day);
+        assert_eq!(events[2].end, NaiveDate::from_ymd_opt(2024, 8, 27).unwrap());
+    }
+
+    #[test]
+    fn test_out_of_office_days() {
+        let days: Vec<String> = parse_events(CALENDAR)
+            .into_iter()
+            .filter(CalendarEvent::is_out_of_office)
+            .flat_map(|event| event.days())
+            .map(|day| day.format("%Y-%m-%d").to_string())
+            .collect();
+
+        assert_eq!(days, vec!["2024-08-19", "2024-08-20", "2024-08-26"]);
+    }
+
+    #[test]
+    fn test_write_calendar_can_be_parsed_back() {
+        let day = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
+        let events = vec![
+            ExportEvent {
+                uid: "vacation-2024-08-01".to_string(),
+                summary: "Vacation, all day".to_string(),
+                time: EventTime::AllDay(day),
+            },
+            ExportEvent {
+                uid: "entry-2024-08-01".to_string(),
+                summary: "Planned entry ".repeat(10),
+                time: EventTime::Timed(day.and_hms_opt(8, 0, 0).unwrap(), day.and_hms_opt(16, 0, 0).unwrap()),
+            },
+        ];
+
+        let calendar = write_calendar(&events, Utc::now());
+        let parsed = parse_events(&calendar);
+
+        assert!(calendar.lines().all(|line| line.len() <= 76));
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].summary, "Vacation, all day");
+        assert!(parsed[0].all_day);
+        assert_eq!(parsed[1].summary, "Planned entry ".repeat(10));
+        assert!(!parsed[1].all_day);
+    }
+}
@@ -1,59 +1,507 @@
-use std::io;
+use std::{collections::HashMap, io};
 
-use bambo_processor::BambooProcessor;
+use approvals::Decision;
+use bambo_processor::{BambooProcessor, EmployeeReport, TimesheetStatus};
 use bamboo_client::BambooClient;
-use days_calculator::{get_eligible_days_this_month, get_weekday, get_working_days_this_month};
+use chrono::Datelike;
+use clap::Parser;
+use config::{CapacityFormat, Command, Config, ProgramArguments};
+use days_calculator::{get_weekday, DateRange};
 use itertools::Itertools;
+use model::{clock_in::clock_in_request::ClockInRequest, get_timeoff_requests::timeoff_request::TimeOffRequest};
+use month_review::MonthReview;
+use notification::Notification;
+use output::{print_json, ApprovalOutcome, OutputFormat, StatusOutput};
+use plan_file::{ApplyResult, PlanFile};
+use serde_json::json;
+use tracing_subscriber::EnvFilter;
 
+mod approvals;
 mod bambo_processor;
 mod bamboo_client;
+mod calendar_export;
+mod capacity;
+mod clock;
 mod config;
+mod daemon;
+mod dashboard;
 mod days_calculator;
+mod email_notifier;
+mod entry_editor;
+mod entry_planner;
+mod fixtures;
+mod ics;
 mod model;
+mod month_review;
+mod notification;
+mod output;
+mod plan_file;
+mod public_holidays;
+mod scheduler;
+mod secret;
+mod server;
+mod submission_lock;
+mod timeoff_balance;
+mod timeoff_request;
+mod tui;
+mod webhook_notifier;
+mod whos_out_grid;
+mod work_schedule;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let config = config::load_config()?;
-    
+    let program_args = ProgramArguments::parse();
+    init_tracing(program_args.verbose);
+    let config = config::load_config(&program_args)?;
+
     let bamboo_client = BambooClient::new(&config);
     let bambo_processor = BambooProcessor::new(&bamboo_client);
 
-    let working_days_this_month = get_working_days_this_month();
-    let bank_holidays = bambo_processor.get_bank_holidays().await?;
-    let vacation_days = bambo_processor.get_vacation_days().await?;
-    let already_added_days = bambo_processor.get_already_added_days().await?;
+    let json = config.output == OutputFormat::Json;
 
-    let eligible_days = get_eligible_days_this_month(
-        &working_days_this_month,
-        &vacation_days,
-        &already_added_days,
-        &bank_holidays,
+    match program_args.command {
+        None => run_fill(&bambo_processor, &config, &DateRange::this_month(), program_args.tui, false).await,
+        Some(Command::Fill { from, to, yes }) => {
+            let range = DateRange::parse(from.as_deref(), to.as_deref())?;
+            run_fill(&bambo_processor, &config, &range, program_args.tui, yes).await
+        }
+        Some(Command::Export { from, to, output }) => {
+            let range = DateRange::parse(from.as_deref(), to.as_deref())?;
+            let status = bambo_processor.get_timesheet_status(&range).await?;
+            let events = calendar_export::export_calendar(&status, &config.company, &output)?;
+            if json {
+                return print_json(&json!({ "from": range.start, "to": range.end, "output": output, "events": events }));
+            }
+            println!("Exported {events} events from {} to {} into {output}", range.start, range.end);
+            Ok(())
+        }
+        Some(Command::Plan { from, to, output }) => {
+            let range = DateRange::parse(from.as_deref(), to.as_deref())?;
+            let plan = bambo_processor.create_plan(&range).await?;
+            plan.save(&output)?;
+            if json {
+                return print_json(&plan);
+            }
+            println!("{}", plan.summary());
+            println!("Saved into {output}. Review it and run apply to execute it.");
+            Ok(())
+        }
+        Some(Command::Apply { plan }) => {
+            let plan = PlanFile::load(&plan)?;
+            let result = if plan.is_empty() {
+                ApplyResult::default()
+            } else {
+                bambo_processor.apply_plan(&plan).await?
+            };
+            if json {
+                return print_json(&result);
+            }
+            println!("{}", plan.summary());
+            if plan.is_empty() {
+                println!("Nothing to do.");
+                return Ok(());
+            }
+            println!(
+                "Deleted {}, updated {}, created {} of {} entries.",
+                result.deleted,
+                result.updated,
+                result.created,
+                plan.create.len()
+            );
+            Ok(())
+        }
+        Some(Command::Serve { listen }) => server::serve(config.clone(), &listen).await,
+        Some(Command::Daemon { trigger, remind_trigger, state_file }) => {
+            let trigger = scheduler::Trigger::parse(&trigger)?;
+            let remind_trigger = remind_trigger.as_deref().map(scheduler::Trigger::parse).transpose()?;
+            daemon::run_daemon(&bambo_processor, &config, &trigger, remind_trigger.as_ref(), &state_file).await
+        }
+        Some(Command::Remind) => {
+            let status = bambo_processor.get_timesheet_status(&DateRange::this_month()).await?;
+            let missing_days: Vec<String> = status.eligible_days.iter().sorted().cloned().collect();
+            let sent = if missing_days.is_empty() {
+                0
+            } else {
+                notification::send_notification(&config, &Notification::MissingDays { days: missing_days.clone() }).await?
+            };
+            if json {
+                return print_json(&json!({ "missingDays": missing_days, "notificationsSent": sent }));
+            }
+            match (missing_days.len(), sent) {
+                (0, _) => println!("Nothing to remind about."),
+                (_, 0) => println!("No notifier configured, set up SMTP or webhook to send reminders."),
+                (missing, _) => println!("Reminder about {missing} missing days sent."),
+            }
+            Ok(())
+        }
+        Some(Command::TeamReport { from, to, direct_reports }) => {
+            let range = DateRange::parse(from.as_deref(), to.as_deref())?;
+            let employee_ids = if direct_reports {
+                bambo_processor.get_direct_reports().await?
+            } else {
+                config.team.clone()
+            };
+            if employee_ids.is_empty() {
+                eprintln!("No team members. Use --team=<ID,ID,...>, env variable TEAM_EMPLOYEE_IDS or --direct-reports.");
+                return if json { print_json(&[] as &[EmployeeReport]) } else { Ok(()) };
+            }
+            let reports = bambo_processor.get_team_report(&range, &employee_ids).await?;
+            if json {
+                return print_json(&reports);
+            }
+            for report in reports {
+                if report.missing_days.is_empty() {
+                    println!("{} (#{}): complete", report.name, report.employee_id);
+                } else {
+                    println!("{} (#{}): missing {} days", report.name, report.employee_id, report.missing_days.len());
+                    for day in report.missing_days.iter().sorted() {
+                        println!("  {day} ({})", get_weekday(day)?);
+                    }
+                }
+            }
+            Ok(())
+        }
+        Some(Command::WhosOut { from, to, department }) => {
+            let start = from.as_deref().map(days_calculator::parse_date).transpose()?;
+            let default_range = match start {
+                Some(start) => DateRange::new(start, start + chrono::Days::new(13))?,
+                None => DateRange::next_days(14),
+            };
+            let range = DateRange::parse_or(from.as_deref(), to.as_deref(), default_range)?;
+            let entries = bambo_processor.get_whos_out(&range, department.as_deref(), &config.team).await?;
+            if json {
+                return print_json(&entries);
+            }
+            println!("{}", whos_out_grid::render_whos_out(&range, &entries, &config.schedule));
+            Ok(())
+        }
+        Some(Command::Capacity { from, to, part_time, format }) => {
+            let range = DateRange::parse(Some(&from), Some(&to))?;
+            if config.team.is_empty() {
+                return Err("No team members. Use --team=<ID,ID,...> or env variable TEAM_EMPLOYEE_IDS.".into());
+            }
+            let work_start = config.schedule.start.format("%H:%M").to_string();
+            let part_time = part_time
+                .iter()
+                .map(|value| capacity::parse_part_time(value, &work_start))
+                .collect::<Result<HashMap<_, _>, String>>()?;
+            let sprint_capacity = bambo_processor.get_sprint_capacity(&range, &config.team, &part_time).await?;
+            match (format, json) {
+                (CapacityFormat::Json, _) | (_, true) => print_json(&sprint_capacity)?,
+                (CapacityFormat::Table, false) => println!("{}", capacity::render_table(&sprint_capacity)),
+            }
+            Ok(())
+        }
+        Some(Command::Balance) => {
+            let balances = bambo_processor.get_timeoff_balances().await?;
+            if json {
+                return print_json(&balances);
+            }
+            if balances.is_empty() {
+                println!("No time off policies assigned.");
+                return Ok(());
+            }
+            let year_end = format!("{}-12-31", chrono::Local::now().year());
+            println!("{}", timeoff_balance::render_balances(&balances, &year_end));
+            Ok(())
+        }
+        Some(Command::RequestTimeoff { time_off_type, from, to, day, note, yes }) => {
+            if json && !yes {
+                return Err("Requesting time off with --output=json requires --yes".into());
+            }
+            let range = DateRange::parse(Some(&from), Some(to.as_deref().unwrap_or(&from)))?;
+            let day_amounts = day
+                .iter()
+                .map(|value| timeoff_request::parse_day_amount(value))
+                .collect::<Result<Vec<_>, String>>()?;
+            let request = bambo_processor
+                .build_timeoff_request(&range, &time_off_type, &day_amounts, note.as_deref())
+                .await?;
+            if !json {
+                println!("Requesting {} {} from {} to {}:", request.amount, time_off_type, request.start, request.end);
+                for date in &request.dates {
+                    println!("{} ({}): {}", date.ymd, get_weekday(&date.ymd)?, date.amount);
+                }
+            }
+            if !yes {
+                println!("Do you want to submit the request? (y/n)");
+                let mut user_response = String::new();
+                io::stdin().read_line(&mut user_response).expect("Failed to read line");
+                if user_response.trim().to_lowercase() != "y" {
+                    println!("Nothing requested.");
+                    return Ok(());
+                }
+            }
+            let response = bamboo_client.add_timeoff_request(&request).await?;
+            if json {
+                return print_json(&json!({ "request": request, "response": response }));
+            }
+            println!("Request {} is {} (last changed {})", response.id, response.status.status, response.status.last_changed);
+            Ok(())
+        }
+        Some(Command::Approvals { from, to, approve_all, deny_all, note }) => {
+            let range = DateRange::parse_or(from.as_deref(), to.as_deref(), DateRange::next_days(365))?;
+            let pending = bambo_processor.get_pending_approvals(&range).await?;
+            let bulk_decision = match (approve_all, deny_all) {
+                (true, _) => Some(Decision::Approve),
+                (_, true) => Some(Decision::Deny),
+                _ => None,
+            };
+            match (json, bulk_decision) {
+                (true, None) => print_json(&pending),
+                (true, Some(decision)) => {
+                    let mut outcomes = Vec::new();
+                    for request in &pending {
+                        let error = bambo_processor
+                            .decide_timeoff_request(request, decision, note.as_deref())
+                            .await
+                            .err()
+                            .map(|e| e.to_string());
+                        outcomes.push(ApprovalOutcome::new(request, decision, error));
+                    }
+                    print_json(&outcomes)
+                }
+                (false, _) if pending.is_empty() => {
+                    println!("No pending requests.");
+                    Ok(())
+                }
+                (false, _) => review_approvals(&bambo_processor, &pending, bulk_decision, note.as_deref()).await,
+            }
+        }
+        Some(Command::ClockIn { project, task, note }) => {
+            let request = ClockInRequest { project_id: project, task_id: task, note };
+            let entry = bamboo_client.clock_in(&request).await?;
+            if json {
+                return print_json(&entry);
+            }
+            println!("Clocked in at {}", clock::format_entry_time(entry.start.as_deref()));
+            Ok(())
+        }
+        Some(Command::ClockOut) => {
+            let entry = bamboo_client.clock_out().await?;
+            if json {
+                return print_json(&entry);
+            }
+            println!("Clocked out at {}", clock::format_entry_time(entry.end.as_deref()));
+            Ok(())
+        }
+        Some(Command::Status) => {
+            let status = bambo_processor.get_clock_status().await?;
+            if json {
+                return print_json(&status);
+            }
+            println!("{}", clock::render_status(&status));
+            Ok(())
+        }
+        Some(Command::ShiftDay { date, by }) => {
+            let day = days_calculator::parse_date(&date)?;
+            let shift = entry_editor::parse_shift(&by)?;
+            let updated = bambo_processor.shift_day(&day, shift).await?;
+            if json {
+                return print_json(&json!({ "date": date, "shift": by, "updated": updated }));
+            }
+            if updated == 0 {
+                println!("No clock entries on {date}.");
+            } else {
+                println!("Moved {updated} entries on {date} by {by}.");
+            }
+            Ok(())
+        }
+        Some(Command::DeleteEntries { from, to, yes }) => {
+            if json && !yes {
+                return Err("Deleting entries with --output=json requires --yes".into());
+            }
+            let range = DateRange::parse(Some(&from), Some(to.as_deref().unwrap_or(&from)))?;
+            let entries = bambo_processor.get_clock_entries(&range).await?;
+            if entries.is_empty() {
+                return if json { print_json(&json!({ "deleted": [] })) } else {
+                    println!("No clock entries in the range.");
+                    Ok(())
+                };
+            }
+            if !json {
+                println!("Entries to delete:");
+                for entry in &entries {
+                    println!(
+                        "{} ({}): {} - {}",
+                        entry.date,
+                        get_weekday(&entry.date)?,
+                        clock::format_entry_time(entry.start.as_deref()),
+                        clock::format_entry_time(entry.end.as_deref())
+                    );
+                }
+            }
+            if !yes {
+                println!("Do you want to delete {} entries? (y/n)", entries.len());
+                let mut user_response = String::new();
+                io::stdin().read_line(&mut user_response).expect("Failed to read line");
+                if user_response.trim().to_lowercase() != "y" {
+                    println!("Nothing deleted.");
+                    return Ok(());
+                }
+            }
+            let entry_ids: Vec<u32> = entries.iter().map(|entry| entry.id).collect();
+            bamboo_client.delete_time_entries(&entry_ids).await?;
+            if json {
+                return print_json(&json!({ "deleted": entry_ids }));
+            }
+            println!("Deleted {} entries.", entry_ids.len());
+            Ok(())
+        }
+    }
+}
+
+/// Logs go to stderr so they never mix with the output. RUST_LOG overrides the verbosity flag.
+fn init_tracing(verbose: u8) {
+    let default_filter = match verbose {
+        0 => "warn,bamboo_bot=info",
+        1 => "warn,bamboo_bot=debug",
+        _ => "warn,bamboo_bot=trace",
+    };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter));
+    tracing_subscriber::fmt().with_env_filter(filter).with_writer(io::stderr).init();
+}
+
+/// Fills the range interactively, in the terminal UI or without asking with `yes`
+async fn run_fill(
+    bambo_processor: &BambooProcessor<'_>,
+    config: &Config,
+    range: &DateRange,
+    tui: bool,
+    yes: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let json = config.output == OutputFormat::Json;
+    if json && tui {
+        return Err("--tui can't be combined with --output=json".into());
+    }
+    let status = bambo_processor.get_timesheet_status(range).await?;
+    if tui {
+        return fill_tui(bambo_processor, config, range, &status).await;
+    }
+    if !yes {
+        return if json { print_json(&StatusOutput::new(range, &status, None)) } else { fill(bambo_processor, config, &status).await };
+    }
+
+    let result = bambo_processor.submit_entries(&status.entry_plan.entries).await?;
+    if result.added > 0 {
+        let days = status.entry_plan.entries.iter().map(|entry| String::from(&entry.date)).unique().collect();
+        notify(config, &Notification::Filled { days, entries: result.added }).await;
+    }
+    if json {
+        return print_json(&StatusOutput::new(range, &status, Some(result)));
+    }
+    println!(
+        "Added {} of {} entries, {} already in Bamboo.",
+        result.added,
+        status.entry_plan.entries.len(),
+        result.skipped
     );
+    Ok(())
+}
+
+async fn review_approvals(
+    bambo_processor: &BambooProcessor<'_>,
+    pending: &[TimeOffRequest],
+    bulk_decision: Option<Decision>,
+    note: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{} pending requests:", pending.len());
+    let (mut approved, mut denied) = (0, 0);
+    for request in pending {
+        println!("{}", approvals::describe(request));
+        let (decision, request_note) = match bulk_decision {
+            Some(decision) => (decision, note.map(String::from)),
+            None => {
+                println!("Approve, deny or skip? (a/d/s)");
+                let mut answer = String::new();
+                io::stdin().read_line(&mut answer).expect("Failed to read line");
+                let decision = Decision::parse(&answer);
+                if decision == Decision::Skip || note.is_some() {
+                    (decision, note.map(String::from))
+                } else {
+                    println!("Note (leave empty for none):");
+                    let mut request_note = String::new();
+                    io::stdin().read_line(&mut request_note).expect("Failed to read line");
+                    let request_note = request_note.trim();
+                    (decision, (!request_note.is_empty()).then(|| request_note.to_string()))
+                }
+            }
+        };
+        if !decision.is_allowed(request) {
+            println!("Skipped, you are not allowed to set this request to {}.", decision.status().unwrap_or_default());
+            continue;
+        }
 
-    if eligible_days.len() == 0 {
+        match bambo_processor.decide_timeoff_request(request, decision, request_note.as_deref()).await {
+            Ok(()) => match decision {
+                Decision::Approve => {
+                    approved += 1;
+                    println!("Approved.");
+                }
+                Decision::Deny => {
+                    denied += 1;
+                    println!("Denied.");
+                }
+                Decision::Skip => println!("Skipped."),
+            },
+            Err(e) => tracing::error!(request_id = %request.id, error = %e, "Error while changing status"),
+        }
+    }
+    println!("Approved {approved}, denied {denied} of {} requests.", pending.len());
+    Ok(())
+}
+
+async fn fill(bambo_processor: &BambooProcessor<'_>, config: &Config, status: &TimesheetStatus) -> Result<(), Box<dyn std::error::Error>> {
+    if status.eligible_days.is_empty() {
         println!("Nothing to do.");
         return Ok(());
     }
-    if bank_holidays.len() > 0 {
+    if !status.bank_holidays.is_empty() {
         println!("(!) Bank holidays this month:");
-        for bank_holiday in bank_holidays.iter().sorted() {
+        for bank_holiday in status.bank_holidays.iter().sorted() {
             println!("{bank_holiday} ({})", get_weekday(bank_holiday)?);
         }
     }
-    if vacation_days.len() > 0 {
+    if !status.vacation_days.is_empty() {
         println!("(!) You have vacation this month:");
-        for vacation_day in vacation_days.iter().sorted() {
-            println!("{vacation_day} ({})", get_weekday(&vacation_day)?);
+        for vacation_day in status.vacation_days.iter().sorted() {
+            println!("{vacation_day} ({})", get_weekday(vacation_day)?);
+        }
+    }
+    if !status.out_of_office_days.is_empty() {
+        println!("(!) You are out of office this month according to your calendar:");
+        for day in status.out_of_office_days.iter().sorted() {
+            println!("{day} ({})", get_weekday(day)?);
         }
     }
     println!("This month you are missing following days:");
-    for day in eligible_days.iter().sorted() {
+    for day in status.eligible_days.iter().sorted() {
         println!("{day} ({})", get_weekday(day)?);
     }
 
+    if !status.entry_plan.conflicts.is_empty() {
+        println!("(!) Some of the days above collide with existing entries or time off:");
+        for conflict in &status.entry_plan.conflicts {
+            println!(
+                "{} {}-{} overlaps {}",
+                conflict.date,
+                conflict.planned_start.format("%H:%M"),
+                conflict.planned_end.format("%H:%M"),
+                conflict.busy.reason
+            );
+        }
+        for day in &status.entry_plan.skipped_days {
+            println!("{day} is fully taken, it will be skipped");
+        }
+    }
+    if status.entry_plan.entries.is_empty() {
+        println!("Nothing to do.");
+        return Ok(());
+    }
+
     println!(
         "Do you want to send entries to Bamboo for all {} days above? (y/n)",
-        eligible_days.len()
+        status.eligible_days.len() - status.entry_plan.skipped_days.len()
     );
 
     let mut user_response = String::new();
@@ -62,12 +510,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to read line");
 
     if user_response.trim().to_lowercase() == "y" {
-        let response = &bamboo_client.add_time_entries(&eligible_days).await?;
-        if response.status() == 201 {
-            println!("Successfully added {} entries!", eligible_days.len());
-            return Ok(());
-        } else {
-            println!("Error adding entries {:#?}", response);
+        match bambo_processor.add_new_entries(&status.entry_plan.entries).await {
+            Ok(result) => {
+                println!("Successfully added {} entries!", result.added);
+                if result.added > 0 {
+                    let days = status.entry_plan.entries.iter().map(|entry| String::from(&entry.date)).unique().collect();
+                    notify(config, &Notification::Filled { days, entries: result.added }).await;
+                }
+                return Ok(());
+            }
+            Err(e) => tracing::error!(error = %e, "Error adding entries"),
         }
     }
 
@@ -79,8 +531,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("Failed to read line");
 
     if user_response.trim().to_lowercase() == "y" {
-        for day in &eligible_days {
-            println!("Do you want to add timesheet entry on date {}? (y/n)", day);
+        let mut filled_days = Vec::new();
+        let mut added_entries = 0;
+        for day in &status.eligible_days {
+            let day_entries = status.entry_plan.entries_for_day(day);
+            if day_entries.is_empty() {
+                continue;
+            }
+            let hours = day_entries
+                .iter()
+                .map(|entry| format!("{}-{}", entry.start, entry.end))
+                .join(", ");
+            println!("Do you want to add timesheet entry on date {} ({})? (y/n)", day, hours);
             let mut response = String::new();
 
             io::stdin()
@@ -88,21 +550,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .expect("Failed to read line");
 
             if response.trim().to_lowercase() == "y" {
-                let add_entry_response = &bamboo_client.add_time_entry(day).await?;
-                if add_entry_response.status() == 201 {
-                    println!("Entry for {} added successfully!", day);
-                    println!("");
-                } else {
-                    println!("Error adding entry for {}: {:#?}", day, add_entry_response)
+                match bambo_processor.add_new_entries(&day_entries).await {
+                    Ok(result) => {
+                        println!("Entry for {} added successfully!", day);
+                        println!();
+                        if result.added > 0 {
+                            filled_days.push(String::from(day));
+                            added_entries += result.added;
+                        }
+                    }
+                    Err(e) => tracing::error!(%day, error = %e, "Error adding entry"),
                 }
             } else {
                 println!("Skipping adding entry for {}", day);
-                println!("");
+                println!();
             }
         }
+        if added_entries > 0 {
+            notify(config, &Notification::Filled { days: filled_days, entries: added_entries }).await;
+        }
     } else {
         println!("Fine. Have a nice day!");
     }
 
     Ok(())
 }
+
+async fn fill_tui(
+    bambo_processor: &BambooProcessor<'_>,
+    config: &Config,
+    range: &DateRange,
+    status: &TimesheetStatus,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut review = MonthReview::new(range, status, &config.schedule);
+    let Some(entries) = tui::review_month(&mut review)? else {
+        println!("Nothing sent. Have a nice day!");
+        return Ok(());
+    };
+    if entries.is_empty() {
+        println!("Nothing to do.");
+        return Ok(());
+    }
+
+    let result = bambo_processor.submit_entries(&entries).await?;
+    println!("Added {} of {} entries.", result.added, entries.len());
+    if result.added > 0 {
+        let days = entries.iter().map(|entry| String::from(&entry.date)).unique().collect();
+        notify(config, &Notification::Filled { days, entries: result.added }).await;
+    }
+    Ok(())
+}
+
+/// Notifications are best effort, failing to send one doesn't fail the command
+async fn notify(config: &Config, notification: &Notification) {
+    if let Err(e) = notification::send_notification(config, notification).await {
+        tracing::warn!(error = %e, "Error while sending notification");
+    }
+}
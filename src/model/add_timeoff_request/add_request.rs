@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::get_timeoff_requests::status::Status;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTimeOffRequest {
+    pub status: String,
+    pub start: String,
+    pub end: String,
+    pub time_off_type_id: String,
+    pub amount: f32,
+    pub notes: Vec<AddTimeOffNote>,
+    pub dates: Vec<AddTimeOffDate>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AddTimeOffNote {
+    pub from: String, // "employee" or "manager"
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AddTimeOffDate {
+    pub ymd: String,
+    pub amount: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddTimeOffResponse {
+    pub id: String,
+    pub status: Status,
+}
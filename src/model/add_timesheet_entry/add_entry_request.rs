@@ -6,11 +6,19 @@ pub struct AddEntryRequest {
     pub entries: Vec<AddTimesheetEntry>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AddTimesheetEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u32>, // existing entry is updated when set
     pub employee_id: i32,
     pub date: String,
     pub start: String, // hour "09:00"
     pub end: String, // hour "17:00"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeStatusRequest {
+    pub status: String, // "approved", "denied" or "canceled"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use super::employee::Employee;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Directory {
+    pub employees: Vec<Employee>,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeOffBalance {
+    pub time_off_type: String,
+    pub name: String,
+    pub units: String,
+    pub balance: String,
+    pub end: String,
+    pub policy_type: Option<String>,
+    pub used_year_to_date: Option<String>,
+}
@@ -5,4 +5,5 @@ pub struct RequestType {
     pub id: String,
     pub name: String,
     pub icon: String,
-}
\ No newline at end of file
+    pub units: Option<String>, // only in time off types meta data, "days" or "hours"
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::get_timeoff_requests::request_type::RequestType;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeOffTypes {
+    pub time_off_types: Vec<RequestType>,
+}
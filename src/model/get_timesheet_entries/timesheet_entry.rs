@@ -9,10 +9,10 @@ pub struct TimsheetEntry {
     pub employee_id: i32,
     pub r#type: String, // `type` is a reserved keyword in Rust, so you need to escape it using `r#`.
     pub date: String,
-    pub start: String,
-    pub end: String,
+    pub start: Option<String>, // missing for hour entries
+    pub end: Option<String>, // missing for hour entries and while clocked in
     pub timezone: String,
-    pub hours: u32,
+    pub hours: f32,
     pub note: Option<String>,
     pub project_info: Option<ProjectInfo>,
     pub approved_at: Option<String>,
@@ -20,4 +20,33 @@ pub mod get_timeoff_requests {
     pub mod notes;
     pub mod request_type;
     pub mod status;
-}
\ No newline at end of file
+}
+
+pub mod get_employee_directory {
+    pub mod directory;
+    pub mod employee;
+}
+
+pub mod get_timeoff_balances {
+    pub mod timeoff_balance;
+}
+
+pub mod get_timeoff_types {
+    pub mod timeoff_types;
+}
+
+pub mod add_timeoff_request {
+    pub mod add_request;
+}
+
+pub mod change_timeoff_status {
+    pub mod change_status_request;
+}
+
+pub mod clock_in {
+    pub mod clock_in_request;
+}
+
+pub mod delete_clock_entries {
+    pub mod delete_entries_request;
+}
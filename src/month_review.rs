@@ -0,0 +1,248 @@
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::{
+    bambo_processor::TimesheetStatus,
+    days_calculator::{format_date, DateRange},
+    entry_planner::{parse_time, BusyWindow},
+    model::add_timesheet_entry::add_entry_request::AddTimesheetEntry,
+    work_schedule::WorkSchedule,
+};
+
+/// Smallest change of planned hours
+pub const HOURS_STEP_MINUTES: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayKind {
+    NonWorking,
+    Holiday,
+    Vacation,
+    OutOfOffice,
+    Filled,
+    Missing,
+}
+
+impl DayKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DayKind::NonWorking => "non-working",
+            DayKind::Holiday => "holiday",
+            DayKind::Vacation => "vacation",
+            DayKind::OutOfOffice => "out of office",
+            DayKind::Filled => "filled",
+            DayKind::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReviewDay {
+    pub date: NaiveDate,
+    pub kind: DayKind,
+    pub selected: bool,
+    pub entries: Vec<AddTimesheetEntry>,
+    /// Existing entries and time off of the day
+    pub busy: Vec<BusyWindow>,
+}
+
+impl ReviewDay {
+    pub fn minutes(&self) -> i64 {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((parse_time(&entry.end)? - parse_time(&entry.start)?).num_minutes()))
+            .sum()
+    }
+}
+
+/// Days of the range with planned entries, missing days are selected for submission by default
+#[derive(Debug)]
+pub struct MonthReview {
+    pub days: Vec<ReviewDay>,
+    pub cursor: usize,
+}
+
+impl MonthReview {
+    pub fn new(range: &DateRange, status: &TimesheetStatus, schedule: &WorkSchedule) -> Self {
+        let filled_days: Vec<&str> = status.timesheet_entries.iter().map(|entry| entry.date.as_str()).collect();
+        let days: Vec<ReviewDay> = range
+            .days()
+            .map(|date| {
+                let day = format_date(&date);
+                let entries = status.entry_plan.entries_for_day(&day);
+                let kind = if status.bank_holidays.contains(&day) {
+                    DayKind::Holiday
+                } else if !schedule.is_working_day(&date) {
+                    DayKind::NonWorking
+                } else if status.vacation_days.contains(&day) {
+                    DayKind::Vacation
+                } else if status.out_of_office_days.contains(&day) {
+                    DayKind::OutOfOffice
+                } else if status.eligible_days.contains(&day) || !entries.is_empty() {
+                    // partially filled days stay missing while there is something planned for them
+                    DayKind::Missing
+                } else if filled_days.contains(&day.as_str()) {
+                    DayKind::Filled
+                } else {
+                    DayKind::Missing
+                };
+                let busy = status.entry_plan.busy_windows.iter().filter(|busy| busy.date == day).cloned().collect();
+                ReviewDay { date, kind, selected: kind == DayKind::Missing && !entries.is_empty(), entries, busy }
+            })
+            .collect();
+        let cursor = days.iter().position(|day| day.kind == DayKind::Missing).unwrap_or_default();
+        MonthReview { days, cursor }
+    }
+
+    pub fn current(&self) -> Option<&ReviewDay> {
+        self.days.get(self.cursor)
+    }
+
+    pub fn move_cursor(&mut self, days: isize) {
+        let last = self.days.len().saturating_sub(1) as isize;
+        self.cursor = (self.cursor as isize + days).clamp(0, last) as usize;
+    }
+
+    /// Only missing days with planned entries can be submitted
+    pub fn toggle(&mut self) {
+        if let Some(day) = self.days.get_mut(self.cursor) {
+            if day.kind == DayKind::Missing && !day.entries.is_empty() {
+                day.selected = !day.selected;
+            }
+        }
+    }
+
+    /// Moves the end of the last entry of the day, an entry can't get shorter than one step, end after midnight
+    /// or run into an existing entry or time off
+    pub fn adjust_hours(&mut self, minutes: i64) {
+        let Some(day) = self.days.get_mut(self.cursor) else {
+            return;
+        };
+        let Some(entry) = day.entries.last_mut() else {
+            return;
+        };
+        let (Some(start), Some(end)) = (parse_time(&entry.start), parse_time(&entry.end)) else {
+            return;
+        };
+        let (new_end, overflow) = end.overflowing_add_signed(TimeDelta::minutes(minutes));
+        let next_busy = day.busy.iter().map(|busy| busy.start).filter(|busy_start| *busy_start >= end).min();
+        if overflow != 0 || new_end < start + TimeDelta::minutes(HOURS_STEP_MINUTES) || next_busy.is_some_and(|busy_start| new_end > busy_start) {
+            return;
+        }
+        entry.end = new_end.format("%H:%M").to_string();
+    }
+
+    pub fn selected_entries(&self) -> Vec<AddTimesheetEntry> {
+        self.days
+            .iter()
+            .filter(|day| day.selected)
+            .flat_map(|day| day.entries.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entry_planner::{busy_windows_from_entries, plan_entries};
+
+    use super::*;
+
+    fn review() -> MonthReview {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-18")).unwrap();
+        let eligible_days = vec!["2024-08-13".to_string(), "2024-08-16".to_string()];
+        let status = TimesheetStatus {
+            bank_holidays: vec!["2024-08-15".to_string()],
+            vacation_days: vec!["2024-08-14".to_string()],
+            out_of_office_days: vec![],
+            entry_plan: plan_entries(12, &eligible_days, &[], &WorkSchedule::default()),
+            eligible_days,
+            timesheet_entries: vec![serde_json::from_value(serde_json::json!({
+                "id": 1, "employeeId": 12, "type": "clock", "date": "2024-08-12", "start": "08:00", "end": "16:00",
+                "timezone": "Europe/Warsaw", "hours": 8,
+            }))
+            .unwrap()],
+        };
+        MonthReview::new(&range, &status, &WorkSchedule::default())
+    }
+
+    #[test]
+    fn test_day_kinds() {
+        let review = review();
+
+        let kinds: Vec<DayKind> = review.days.iter().map(|day| day.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DayKind::Filled,
+                DayKind::Missing,
+                DayKind::Vacation,
+                DayKind::Holiday,
+                DayKind::Missing,
+                DayKind::NonWorking,
+                DayKind::NonWorking,
+            ]
+        );
+        assert_eq!(review.cursor, 1);
+        assert_eq!(review.selected_entries().len(), 2);
+    }
+
+    #[test]
+    fn test_toggle_and_adjust() {
+        let mut review = review();
+
+        review.toggle();
+        review.move_cursor(3);
+        review.adjust_hours(-HOURS_STEP_MINUTES);
+        review.move_cursor(-1);
+        review.toggle();
+
+        let entries = review.selected_entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].date.as_str(), entries[0].end.as_str()), ("2024-08-16", "15:30"));
+        assert_eq!(review.days[4].minutes(), 450);
+        assert!(!review.days[3].selected);
+    }
+
+    #[test]
+    fn test_adjust_hours_limits() {
+        let mut review = review();
+
+        review.adjust_hours(9 * 60);
+        assert_eq!(review.days[1].entries[0].end, "16:00");
+        review.adjust_hours(-8 * 60);
+        assert_eq!(review.days[1].entries[0].end, "16:00");
+    }
+
+    #[test]
+    fn test_partially_filled_day_is_missing() {
+        let range = DateRange::parse(Some("2024-08-13"), Some("2024-08-13")).unwrap();
+        let eligible_days = vec!["2024-08-13".to_string()];
+        let timesheet_entries = vec![
+            serde_json::from_value(serde_json::json!({
+                "id": 1, "employeeId": 12, "type": "clock", "date": "2024-08-13", "start": "08:00", "end": "12:00",
+                "timezone": "Europe/Warsaw", "hours": 4,
+            }))
+            .unwrap(),
+            serde_json::from_value(serde_json::json!({
+                "id": 2, "employeeId": 12, "type": "clock", "date": "2024-08-13", "start": "16:00", "end": "18:00",
+                "timezone": "Europe/Warsaw", "hours": 2,
+            }))
+            .unwrap(),
+        ];
+        let status = TimesheetStatus {
+            bank_holidays: vec![],
+            vacation_days: vec![],
+            out_of_office_days: vec![],
+            entry_plan: plan_entries(12, &eligible_days, &busy_windows_from_entries(&timesheet_entries), &WorkSchedule::default()),
+            eligible_days,
+            timesheet_entries,
+        };
+
+        let mut review = MonthReview::new(&range, &status, &WorkSchedule::default());
+        review.adjust_hours(HOURS_STEP_MINUTES);
+
+        assert_eq!(review.days[0].kind, DayKind::Missing);
+        assert_eq!(review.cursor, 0);
+        // the existing entry from 16:00 stops the planned 12:00-16:00 entry from growing
+        let entries = review.selected_entries();
+        assert_eq!((entries[0].start.as_str(), entries[0].end.as_str()), ("12:00", "16:00"));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{config::Config, days_calculator::get_weekday, email_notifier::EmailNotifier, webhook_notifier::WebhookNotifier};
+
+/// Message sent to the user by notifiers
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notification {
+    MissingDays { days: Vec<String> },
+    Filled { days: Vec<String>, entries: usize },
+    /// Daemon gave up retrying the run due on `days`
+    RunFailed { days: Vec<String>, error: String },
+}
+
+impl Notification {
+    pub fn subject(&self) -> String {
+        match self {
+            Notification::MissingDays { days } => format!("Timesheet: you are missing {} days this month", days.len()),
+            Notification::Filled { days, .. } => format!("Timesheet: filled {} days", days.len()),
+            Notification::RunFailed { error, .. } => format!("Timesheet: filling failed: {error}"),
+        }
+    }
+
+    pub fn days(&self) -> &[String] {
+        match self {
+            Notification::MissingDays { days } | Notification::Filled { days, .. } | Notification::RunFailed { days, .. } => days,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let header = match self {
+            Notification::MissingDays { .. } => "This month you are missing following days:".to_string(),
+            Notification::Filled { entries, .. } => format!("Bamboo bot added {entries} entries for following days:"),
+            Notification::RunFailed { error, .. } => format!("Bamboo bot gave up retrying ({error}), fill was due on:"),
+        };
+        let days: Vec<String> = self.days().iter().map(|day| format_day(day)).collect();
+        format!("{header}\n{}", days.join("\n"))
+    }
+}
+
+/// Sends the notification with every configured notifier, returns how many of them succeeded.
+/// A failing notifier doesn't stop the others, it is only an error when every configured notifier failed.
+pub async fn send_notification(config: &Config, notification: &Notification) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut sent = 0;
+    let mut errors = Vec::new();
+    if let Some(smtp) = &config.smtp {
+        match EmailNotifier::new(smtp).send(notification).await {
+            Ok(()) => sent += 1,
+            Err(e) => errors.push(format!("email: {e}")),
+        }
+    }
+    if let Some(webhook) = &config.webhook {
+        match WebhookNotifier::new(webhook).send(notification).await {
+            Ok(()) => sent += 1,
+            Err(e) => errors.push(format!("webhook: {e}")),
+        }
+    }
+    for error in &errors {
+        tracing::error!(%error, "Error while sending notification");
+    }
+    if sent == 0 && !errors.is_empty() {
+        return Err(format!("Every notifier failed ({})", errors.join(", ")).into());
+    }
+    Ok(sent)
+}
+
+pub fn format_day(day: &str) -> String {
+    match get_weekday(day) {
+        Ok(weekday) => format!("{day} ({weekday})"),
+        Err(_) => String::from(day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    use crate::{config::ConfigBuilder, email_notifier::SmtpConfig, webhook_notifier::{WebhookConfig, WebhookFormat}};
+
+    use super::*;
+
+    #[test]
+    fn test_missing_days_text() {
+        let notification = Notification::MissingDays {
+            days: vec!["2024-09-23".to_string(), "2024-09-24".to_string()],
+        };
+
+        assert_eq!(notification.subject(), "Timesheet: you are missing 2 days this month");
+        assert_eq!(
+            notification.text(),
+            "This month you are missing following days:\n2024-09-23 (Monday)\n2024-09-24 (Tuesday)"
+        );
+    }
+
+    async fn config_with_failing_smtp(webhook_status: u16) -> (Config, MockServer) {
+        let webhook = MockServer::start().await;
+        Mock::given(method("POST")).respond_with(ResponseTemplate::new(webhook_status)).mount(&webhook).await;
+        // nothing listens on port 1
+        let smtp = SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: 1,
+            username: None,
+            password: None,
+            from: "bot@example.com".to_string(),
+            to: "me@example.com".to_string(),
+            tls: false,
+        };
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .smtp(Some(smtp))
+            .webhook(Some(WebhookConfig { url: webhook.uri().into(), format: WebhookFormat::Slack }))
+            .build()
+            .unwrap();
+        (config, webhook)
+    }
+
+    #[tokio::test]
+    async fn test_failing_notifier_does_not_stop_others() {
+        let notification = Notification::MissingDays { days: vec!["2024-09-23".to_string()] };
+
+        let (config, webhook) = config_with_failing_smtp(200).await;
+        assert_eq!(send_notification(&config, &notification).await.unwrap(), 1);
+        assert_eq!(webhook.received_requests().await.unwrap().len(), 1);
+
+        let (config, _webhook) = config_with_failing_smtp(500).await;
+        assert!(send_notification(&config, &notification).await.is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    approvals::Decision,
+    bambo_processor::{SubmitResult, TimesheetStatus},
+    days_calculator::{format_date, DateRange},
+    model::{add_timesheet_entry::add_entry_request::AddTimesheetEntry, get_timeoff_requests::timeoff_request::TimeOffRequest},
+};
+
+/// Text is meant for people, JSON on stdout for scripts. Logs go to stderr in both cases.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusOutput {
+    pub from: String,
+    pub to: String,
+    pub bank_holidays: Vec<String>,
+    pub vacation_days: Vec<String>,
+    pub out_of_office_days: Vec<String>,
+    pub missing_days: Vec<String>,
+    pub planned_entries: Vec<AddTimesheetEntry>,
+    pub conflicts: Vec<ConflictOutput>,
+    pub skipped_days: Vec<String>,
+    pub submitted: Option<SubmitResult>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictOutput {
+    pub date: String,
+    pub planned_start: String,
+    pub planned_end: String,
+    pub reason: String,
+}
+
+impl StatusOutput {
+    pub fn new(range: &DateRange, status: &TimesheetStatus, submitted: Option<SubmitResult>) -> Self {
+        let sorted = |days: &[String]| {
+            let mut days = days.to_vec();
+            days.sort();
+            days
+        };
+        StatusOutput {
+            from: format_date(&range.start),
+            to: format_date(&range.end),
+            bank_holidays: sorted(&status.bank_holidays),
+            vacation_days: sorted(&status.vacation_days),
+            out_of_office_days: sorted(&status.out_of_office_days),
+            missing_days: sorted(&status.eligible_days),
+            planned_entries: status.entry_plan.entries.clone(),
+            conflicts: status
+                .entry_plan
+                .conflicts
+                .iter()
+                .map(|conflict| ConflictOutput {
+                    date: String::from(&conflict.date),
+                    planned_start: conflict.planned_start.format("%H:%M").to_string(),
+                    planned_end: conflict.planned_end.format("%H:%M").to_string(),
+                    reason: String::from(&conflict.busy.reason),
+                })
+                .collect(),
+            skipped_days: sorted(&status.entry_plan.skipped_days),
+            submitted,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalOutcome {
+    pub id: String,
+    pub employee_id: String,
+    pub name: String,
+    pub status: String,
+    pub error: Option<String>,
+}
+
+impl ApprovalOutcome {
+    pub fn new(request: &TimeOffRequest, decision: Decision, error: Option<String>) -> Self {
+        let status = match &error {
+            Some(_) => String::from(&request.status.status),
+            None => decision.status().unwrap_or(&request.status.status).to_string(),
+        };
+        ApprovalOutcome {
+            id: String::from(&request.id),
+            employee_id: String::from(&request.employee_id),
+            name: String::from(&request.name),
+            status,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{entry_planner::plan_entries, work_schedule::WorkSchedule};
+
+    use super::*;
+
+    #[test]
+    fn test_status_output() {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-16")).unwrap();
+        let eligible_days = vec!["2024-08-14".to_string(), "2024-08-13".to_string()];
+        let status = TimesheetStatus {
+            bank_holidays: vec!["2024-08-15".to_string()],
+            vacation_days: vec![],
+            out_of_office_days: vec![],
+            entry_plan: plan_entries(12, &eligible_days[..1], &[], &WorkSchedule::default()),
+            eligible_days,
+            timesheet_entries: vec![],
+        };
+
+        let output = serde_json::to_value(StatusOutput::new(&range, &status, Some(SubmitResult { added: 1, skipped: 0 }))).unwrap();
+
+        assert_eq!(
+            output,
+            json!({
+                "from": "2024-08-12",
+                "to": "2024-08-16",
+                "bankHolidays": ["2024-08-15"],
+                "vacationDays": [],
+                "outOfOfficeDays": [],
+                "missingDays": ["2024-08-13", "2024-08-14"],
+                "plannedEntries": [{ "employeeId": 12, "date": "2024-08-14", "start": "08:00", "end": "16:00" }],
+                "conflicts": [],
+                "skippedDays": [],
+                "submitted": { "added": 1, "skipped": 0 },
+            })
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::{fmt, fs};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    days_calculator::{format_date, DateRange},
+    entry_planner::RemoteChanges,
+    model::{add_timesheet_entry::add_entry_request::AddTimesheetEntry, get_timesheet_entries::timesheet_entry::TimsheetEntry},
+};
+
+/// Reviewable list of changes to the timesheet with the remote state they were planned against
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanFile {
+    pub employee_id: i32,
+    pub from: String,
+    pub to: String,
+    pub created_at: String,
+    pub create: Vec<AddTimesheetEntry>,
+    pub update: Vec<AddTimesheetEntry>,
+    pub delete: Vec<u32>,
+    pub remote: Vec<RemoteEntry>,
+}
+
+/// Entry already stored in Bamboo when the plan was made
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEntry {
+    pub id: u32,
+    pub date: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub hours: f32,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct ApplyResult {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+/// Apply which failed after some of the changes were already made in Bamboo
+#[derive(Debug)]
+pub struct PartlyApplied {
+    pub applied: ApplyResult,
+    pub error: String,
+}
+
+impl fmt::Display for PartlyApplied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Plan was partly applied (deleted {}, updated {}, created {} entries) before failing: {}. Run plan again for the remaining changes.",
+            self.applied.deleted, self.applied.updated, self.applied.created, self.error
+        )
+    }
+}
+
+impl std::error::Error for PartlyApplied {}
+
+impl PlanFile {
+    pub fn new(employee_id: i32, range: &DateRange, created_at: &str, create: Vec<AddTimesheetEntry>, changes: RemoteChanges, remote: &[TimsheetEntry]) -> Self {
+        PlanFile {
+            employee_id,
+            from: format_date(&range.start),
+            to: format_date(&range.end),
+            created_at: String::from(created_at),
+            create,
+            update: changes.update,
+            delete: changes.delete,
+            remote: snapshot(remote),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Error while reading plan file {path}: {e}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Error while reading plan file {path}: {e}"))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| format!("Error while writing plan file {path}: {e}"))
+    }
+
+    pub fn range(&self) -> Result<DateRange, String> {
+        DateRange::parse(Some(&self.from), Some(&self.to))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.update.is_empty() && self.delete.is_empty()
+    }
+
+    /// Fails when entries in Bamboo are not the same as when the plan was made
+    pub fn check_remote(&self, remote: &[TimsheetEntry]) -> Result<(), String> {
+        let current = snapshot(remote);
+        if current == self.remote {
+            return Ok(());
+        }
+        let added = current.iter().filter(|entry| !self.remote.contains(entry)).count();
+        let removed = self.remote.iter().filter(|entry| !current.contains(entry)).count();
+        Err(format!(
+            "Timesheet changed since the plan was made ({added} entries added or changed, {removed} removed or changed). Run plan again."
+        ))
+    }
+
+    /// Fails when the plan, possibly edited by hand, touches entries which were not in its remote snapshot
+    /// or adds entries for another employee or outside its range
+    pub fn check_entries(&self) -> Result<(), String> {
+        let known = |id: &u32| self.remote.iter().any(|entry| entry.id == *id);
+        if let Some(id) = self.delete.iter().chain(self.update.iter().filter_map(|entry| entry.id.as_ref())).find(|id| !known(id)) {
+            return Err(format!("Entry {id} is not one of the planned employee's entries in {} - {}", self.from, self.to));
+        }
+        if self.update.iter().any(|entry| entry.id.is_none()) {
+            return Err("Updated entries must have an id".to_string());
+        }
+        for entry in self.create.iter().chain(&self.update) {
+            if entry.employee_id != self.employee_id || entry.date < self.from || entry.date > self.to {
+                return Err(format!(
+                    "Entry of employee {} on {} is outside of the plan for employee {} in {} - {}",
+                    entry.employee_id, entry.date, self.employee_id, self.from, self.to
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "Plan for {} - {}: create {}, update {}, delete {} entries",
+            self.from,
+            self.to,
+            self.create.len(),
+            self.update.len(),
+            self.delete.len()
+        )
+    }
+}
+
+fn snapshot(entries: &[TimsheetEntry]) -> Vec<RemoteEntry> {
+    let mut snapshot: Vec<RemoteEntry> = entries
+        .iter()
+        .map(|entry| RemoteEntry {
+            id: entry.id,
+            date: String::from(&entry.date),
+            start: entry.start.clone(),
+            end: entry.end.clone(),
+            hours: entry.hours,
+        })
+        .collect();
+    snapshot.sort_by_key(|entry| entry.id);
+    snapshot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32, start: &str, end: &str) -> TimsheetEntry {
+        TimsheetEntry {
+            id,
+            employee_id: 12,
+            r#type: "clock".to_string(),
+            date: "2024-08-19".to_string(),
+            start: Some(start.to_string()),
+            end: Some(end.to_string()),
+            timezone: "Europe/Warsaw".to_string(),
+            hours: 0.0,
+            note: None,
+            project_info: None,
+            approved_at: None,
+            approved: None,
+        }
+    }
+
+    fn plan(remote: &[TimsheetEntry]) -> PlanFile {
+        let range = DateRange::parse(Some("2024-08-19"), Some("2024-08-20")).unwrap();
+        let create = vec![AddTimesheetEntry {
+            id: None,
+            employee_id: 12,
+            date: "2024-08-20".to_string(),
+            start: "08:00".to_string(),
+            end: "16:00".to_string(),
+            project_id: None,
+            task_id: None,
+            note: None,
+        }];
+        let changes = RemoteChanges { update: Vec::new(), delete: remote.iter().map(|entry| entry.id).collect() };
+        PlanFile::new(12, &range, "2024-08-20T17:00:00", create, changes, remote)
+    }
+
+    #[test]
+    fn test_check_remote() {
+        let plan = plan(&[entry(1, "08:00", "12:00"), entry(2, "12:30", "16:30")]);
+
+        assert!(plan.check_remote(&[entry(2, "12:30", "16:30"), entry(1, "08:00", "12:00")]).is_ok());
+        assert_eq!(
+            plan.check_remote(&[entry(1, "08:00", "12:00"), entry(2, "12:30", "17:00")]).unwrap_err(),
+            "Timesheet changed since the plan was made (1 entries added or changed, 1 removed or changed). Run plan again."
+        );
+        assert!(plan.check_remote(&[entry(1, "08:00", "12:00")]).is_err());
+    }
+
+    #[test]
+    fn test_plan_round_trip() {
+        let plan = plan(&[entry(1, "08:00", "12:00")]);
+
+        let json = serde_json::to_string_pretty(&plan).unwrap();
+
+        assert_eq!(serde_json::from_str::<PlanFile>(&json).unwrap(), plan);
+        assert_eq!(plan.summary(), "Plan for 2024-08-19 - 2024-08-20: create 1, update 0, delete 1 entries");
+    }
+
+    #[test]
+    fn test_check_entries() {
+        let mut plan = plan(&[entry(1, "08:00", "12:00")]);
+        assert!(plan.check_entries().is_ok());
+
+        plan.delete.push(7);
+        assert_eq!(plan.check_entries().unwrap_err(), "Entry 7 is not one of the planned employee's entries in 2024-08-19 - 2024-08-20");
+
+        plan.delete.pop();
+        plan.create[0].date = "2024-08-21".to_string();
+        assert!(plan.check_entries().is_err());
+
+        plan.create[0].date = "2024-08-20".to_string();
+        plan.create[0].employee_id = 13;
+        assert!(plan.check_entries().is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::fs;
+
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicHoliday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+enum RuleDate {
+    Fixed(u32, u32),
+    Easter(i64),
+    /// Wednesday before 23rd of November (Buß- und Bettag)
+    RepentanceDay,
+}
+
+struct HolidayRule {
+    date: RuleDate,
+    name: &'static str,
+    /// Regions where the holiday applies, empty means the whole country
+    regions: &'static [&'static str],
+    from_year: i32,
+}
+
+const fn national(date: RuleDate, name: &'static str) -> HolidayRule {
+    HolidayRule { date, name, regions: &[], from_year: 0 }
+}
+
+const fn regional(date: RuleDate, name: &'static str, regions: &'static [&'static str]) -> HolidayRule {
+    HolidayRule { date, name, regions, from_year: 0 }
+}
+
+const fn since(year: i32, rule: HolidayRule) -> HolidayRule {
+    HolidayRule { from_year: year, ..rule }
+}
+
+const PL: &[HolidayRule] = &[
+    national(RuleDate::Fixed(1, 1), "Nowy Rok"),
+    national(RuleDate::Fixed(1, 6), "Święto Trzech Króli"),
+    national(RuleDate::Easter(0), "Wielkanoc"),
+    national(RuleDate::Easter(1), "Poniedziałek Wielkanocny"),
+    national(RuleDate::Fixed(5, 1), "Święto Pracy"),
+    national(RuleDate::Fixed(5, 3), "Święto Konstytucji 3 Maja"),
+    national(RuleDate::Easter(49), "Zielone Świątki"),
+    national(RuleDate::Easter(60), "Boże Ciało"),
+    national(RuleDate::Fixed(8, 15), "Wniebowzięcie Najświętszej Maryi Panny"),
+    national(RuleDate::Fixed(11, 1), "Wszystkich Świętych"),
+    national(RuleDate::Fixed(11, 11), "Narodowe Święto Niepodległości"),
+    since(2025, national(RuleDate::Fixed(12, 24), "Wigilia Bożego Narodzenia")),
+    national(RuleDate::Fixed(12, 25), "Boże Narodzenie (pierwszy dzień)"),
+    national(RuleDate::Fixed(12, 26), "Boże Narodzenie (drugi dzień)"),
+];
+
+const DE_REGIONS: &[&str] = &[
+    "BW", "BY", "BE", "BB", "HB", "HH", "HE", "MV", "NI", "NW", "RP", "SL", "SN", "ST", "SH", "TH",
+];
+
+const DE: &[HolidayRule] = &[
+    national(RuleDate::Fixed(1, 1), "Neujahr"),
+    regional(RuleDate::Fixed(1, 6), "Heilige Drei Könige", &["BW", "BY", "ST"]),
+    since(2019, regional(RuleDate::Fixed(3, 8), "Internationaler Frauentag", &["BE"])),
+    since(2023, regional(RuleDate::Fixed(3, 8), "Internationaler Frauentag", &["MV"])),
+    national(RuleDate::Easter(-2), "Karfreitag"),
+    regional(RuleDate::Easter(0), "Ostersonntag", &["BB"]),
+    national(RuleDate::Easter(1), "Ostermontag"),
+    national(RuleDate::Fixed(5, 1), "Tag der Arbeit"),
+    national(RuleDate::Easter(39), "Christi Himmelfahrt"),
+    regional(RuleDate::Easter(49), "Pfingstsonntag", &["BB"]),
+    national(RuleDate::Easter(50), "Pfingstmontag"),
+    regional(RuleDate::Easter(60), "Fronleichnam", &["BW", "BY", "HE", "NW", "RP", "SL"]),
+    regional(RuleDate::Fixed(8, 15), "Mariä Himmelfahrt", &["SL"]),
+    since(2019, regional(RuleDate::Fixed(9, 20), "Weltkindertag", &["TH"])),
+    national(RuleDate::Fixed(10, 3), "Tag der Deutschen Einheit"),
+    regional(RuleDate::Fixed(10, 31), "Reformationstag", &["BB", "MV", "SN", "ST", "TH"]),
+    since(2018, regional(RuleDate::Fixed(10, 31), "Reformationstag", &["HB", "HH", "NI", "SH"])),
+    regional(RuleDate::Fixed(11, 1), "Allerheiligen", &["BW", "BY", "NW", "RP", "SL"]),
+    regional(RuleDate::RepentanceDay, "Buß- und Bettag", &["SN"]),
+    national(RuleDate::Fixed(12, 25), "1. Weihnachtstag"),
+    national(RuleDate::Fixed(12, 26), "2. Weihnachtstag"),
+];
+
+const AT: &[HolidayRule] = &[
+    national(RuleDate::Fixed(1, 1), "Neujahr"),
+    national(RuleDate::Fixed(1, 6), "Heilige Drei Könige"),
+    national(RuleDate::Easter(1), "Ostermontag"),
+    national(RuleDate::Fixed(5, 1), "Staatsfeiertag"),
+    national(RuleDate::Easter(39), "Christi Himmelfahrt"),
+    national(RuleDate::Easter(50), "Pfingstmontag"),
+    national(RuleDate::Easter(60), "Fronleichnam"),
+    national(RuleDate::Fixed(8, 15), "Mariä Himmelfahrt"),
+    national(RuleDate::Fixed(10, 26), "Nationalfeiertag"),
+    national(RuleDate::Fixed(11, 1), "Allerheiligen"),
+    national(RuleDate::Fixed(12, 8), "Mariä Empfängnis"),
+    national(RuleDate::Fixed(12, 25), "Christtag"),
+    national(RuleDate::Fixed(12, 26), "Stefanitag"),
+];
+
+const CZ: &[HolidayRule] = &[
+    national(RuleDate::Fixed(1, 1), "Den obnovy samostatného českého státu"),
+    since(2016, national(RuleDate::Easter(-2), "Velký pátek")),
+    national(RuleDate::Easter(1), "Velikonoční pondělí"),
+    national(RuleDate::Fixed(5, 1), "Svátek práce"),
+    national(RuleDate::Fixed(5, 8), "Den vítězství"),
+    national(RuleDate::Fixed(7, 5), "Den slovanských věrozvěstů Cyrila a Metoděje"),
+    national(RuleDate::Fixed(7, 6), "Den upálení mistra Jana Husa"),
+    national(RuleDate::Fixed(9, 28), "Den české státnosti"),
+    national(RuleDate::Fixed(10, 28), "Den vzniku samostatného československého státu"),
+    national(RuleDate::Fixed(11, 17), "Den boje za svobodu a demokracii"),
+    national(RuleDate::Fixed(12, 24), "Štědrý den"),
+    national(RuleDate::Fixed(12, 25), "1. svátek vánoční"),
+    national(RuleDate::Fixed(12, 26), "2. svátek vánoční"),
+];
+
+fn country_rules(country: &str) -> Option<(&'static [HolidayRule], &'static [&'static str])> {
+    match country {
+        "PL" => Some((PL, &[])),
+        "DE" => Some((DE, DE_REGIONS)),
+        "AT" => Some((AT, &[])),
+        "CZ" => Some((CZ, &[])),
+        _ => None,
+    }
+}
+
+/// Checks that bundled rules exist for given country and region (e.g. "DE", "BY").
+pub fn validate_calendar(country: &str, region: Option<&str>) -> Result<(), String> {
+    let (_, regions) = country_rules(&country.to_uppercase())
+        .ok_or(format!("Public holidays for country {country} are not supported. Supported countries: PL, DE, AT, CZ"))?;
+    if let Some(region) = region {
+        if !regions.contains(&region.to_uppercase().as_str()) {
+            return Err(format!(
+                "Unknown region {region} for country {country}. Known regions: {}",
+                regions.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Public holidays between `from` and `to` (both inclusive) from the bundled rules.
+pub fn get_public_holidays(
+    country: &str,
+    region: Option<&str>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<PublicHoliday>, String> {
+    validate_calendar(country, region)?;
+    let (rules, _) = country_rules(&country.to_uppercase()).unwrap();
+    let region = region.map(|region| region.to_uppercase());
+
+    let mut holidays = Vec::new();
+    for year in from.year()..=to.year() {
+        for rule in rules {
+            if year < rule.from_year {
+                continue;
+            }
+            if !rule.regions.is_empty()
+                && !region.as_ref().is_some_and(|region| rule.regions.contains(&region.as_str()))
+            {
+                continue;
+            }
+            let date = match rule.date {
+                RuleDate::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+                RuleDate::Easter(offset) => easter_sunday(year) + TimeDelta::days(offset),
+                RuleDate::RepentanceDay => repentance_day(year),
+            };
+            if date >= from && date <= to {
+                holidays.push(PublicHoliday { date, name: rule.name.to_string() });
+            }
+        }
+    }
+    holidays.sort_by_key(|holiday| holiday.date);
+    Ok(holidays)
+}
+
+/// Reads holidays from a text file, one "YYYY-MM-DD [name]" per line. Lines starting with # are ignored.
+pub fn load_holidays_file(path: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<PublicHoliday>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Error while reading holidays file {path}: {e}"))?;
+
+    let mut holidays = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (date, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date {date} in holidays file {path}: {e}"))?;
+        if date >= from && date <= to {
+            holidays.push(PublicHoliday { date, name: name.trim().to_string() });
+        }
+    }
+    Ok(holidays)
+}
+
+/// Anonymous Gregorian algorithm (Meeus/Jones/Butcher)
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap()
+}
+
+fn repentance_day(year: i32) -> NaiveDate {
+    let mut date = NaiveDate::from_ymd_opt(year, 11, 22).unwrap();
+    while date.weekday() != Weekday::Wed {
+        date = date.pred_opt().unwrap();
+    }
+    date
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn holiday_dates(country: &str, region: Option<&str>, year: i32) -> Vec<String> {
+        get_public_holidays(country, region, date(&format!("{year}-01-01")), date(&format!("{year}-12-31")))
+            .unwrap()
+            .into_iter()
+            .map(|holiday| holiday.date.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(easter_sunday(2024), date("2024-03-31"));
+        assert_eq!(easter_sunday(2025), date("2025-04-20"));
+        assert_eq!(easter_sunday(2019), date("2019-04-21"));
+    }
+
+    #[test]
+    fn test_polish_holidays() {
+        let holidays = holiday_dates("PL", None, 2024);
+
+        assert_eq!(holidays.len(), 13);
+        assert!(holidays.contains(&"2024-05-30".to_string()));
+        assert!(!holidays.contains(&"2024-12-24".to_string()));
+        assert!(holiday_dates("pl", None, 2025).contains(&"2025-12-24".to_string()));
+    }
+
+    #[test]
+    fn test_german_regional_holidays() {
+        let bavaria = holiday_dates("DE", Some("BY"), 2024);
+        let berlin = holiday_dates("DE", Some("BE"), 2024);
+        let saxony = holiday_dates("DE", Some("SN"), 2024);
+
+        assert!(bavaria.contains(&"2024-01-06".to_string()));
+        assert!(!berlin.contains(&"2024-01-06".to_string()));
+        assert!(berlin.contains(&"2024-03-08".to_string()));
+        assert!(saxony.contains(&"2024-11-20".to_string()));
+        assert_eq!(holiday_dates("DE", None, 2024).len(), 9);
+    }
+
+    #[test]
+    fn test_validate_calendar() {
+        assert!(validate_calendar("DE", Some("by")).is_ok());
+        assert!(validate_calendar("DE", Some("XX")).is_err());
+        assert!(validate_calendar("XX", None).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::work_schedule::WorkSchedule;
+
+/// When the daemon fills timesheets
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    EveryWorkingDay(NaiveTime),
+    LastWorkingDayOfMonth(NaiveTime),
+}
+
+impl Trigger {
+    /// Parses "weekdays@17:00" or "last-working-day@17:00"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (kind, time) = value.split_once('@').unwrap_or((value, "17:00"));
+        let time = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+            .map_err(|e| format!("Invalid time {time} in trigger {value}, expected HH:MM: {e}"))?;
+        match kind.trim() {
+            "weekdays" => Ok(Trigger::EveryWorkingDay(time)),
+            "last-working-day" => Ok(Trigger::LastWorkingDayOfMonth(time)),
+            _ => Err(format!(
+                "Unknown trigger {value}, expected weekdays@HH:MM or last-working-day@HH:MM"
+            )),
+        }
+    }
+
+    /// First moment strictly after `after` matching the trigger, working days are taken from the schedule
+    pub fn next_after(&self, after: NaiveDateTime, schedule: &WorkSchedule) -> Option<NaiveDateTime> {
+        let time = match self {
+            Trigger::EveryWorkingDay(time) | Trigger::LastWorkingDayOfMonth(time) => *time,
+        };
+        after
+            .date()
+            .iter_days()
+            .take(400)
+            .filter(|day| self.matches(day, schedule))
+            .map(|day| day.and_time(time))
+            .find(|run| *run > after)
+    }
+
+    fn matches(&self, day: &NaiveDate, schedule: &WorkSchedule) -> bool {
+        match self {
+            Trigger::EveryWorkingDay(_) => schedule.is_working_day(day),
+            Trigger::LastWorkingDayOfMonth(_) => {
+                schedule.is_working_day(day)
+                    && !day
+                        .iter_days()
+                        .skip(1)
+                        .take_while(|next| next.month() == day.month())
+                        .any(|next| schedule.is_working_day(&next))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_parse_trigger() {
+        assert_eq!(
+            Trigger::parse("weekdays@17:00").unwrap(),
+            Trigger::EveryWorkingDay(NaiveTime::from_hms_opt(17, 0, 0).unwrap())
+        );
+        assert_eq!(
+            Trigger::parse("last-working-day@09:30").unwrap(),
+            Trigger::LastWorkingDayOfMonth(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+        );
+        assert!(Trigger::parse("sometimes@17:00").is_err());
+        assert!(Trigger::parse("weekdays@5pm").is_err());
+    }
+
+    #[test]
+    fn test_every_working_day_skips_weekend() {
+        let trigger = Trigger::parse("weekdays@17:00").unwrap();
+        let schedule = WorkSchedule::default();
+
+        // friday after 17:00 -> monday
+        assert_eq!(
+            trigger.next_after(date_time("2024-08-02 17:00"), &schedule),
+            Some(date_time("2024-08-05 17:00"))
+        );
+        assert_eq!(
+            trigger.next_after(date_time("2024-08-05 09:00"), &schedule),
+            Some(date_time("2024-08-05 17:00"))
+        );
+    }
+
+    #[test]
+    fn test_last_working_day_of_month() {
+        let trigger = Trigger::parse("last-working-day@17:00").unwrap();
+
+        // 2024-08-31 is saturday
+        assert_eq!(
+            trigger.next_after(date_time("2024-08-01 08:00"), &WorkSchedule::default()),
+            Some(date_time("2024-08-30 17:00"))
+        );
+        assert_eq!(
+            trigger.next_after(date_time("2024-08-01 08:00"), &WorkSchedule::parse("mon-thu", 0.8, "08:00").unwrap()),
+            Some(date_time("2024-08-29 17:00"))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::Deserialize;
+
+/// Credential which never shows up in Debug or Display output, so configs and errors can be logged safely.
+/// The value is only available through `expose`, it is deliberately not Serialize.
+#[derive(Deserialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+impl FromStr for Secret {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Secret::from(value))
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::config::ProgramArguments;
+
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret::from("api-key-123");
+
+        assert_eq!(format!("{secret:?} {secret}"), "[redacted] [redacted]");
+        assert_eq!(secret.expose(), "api-key-123");
+    }
+
+    #[test]
+    fn test_program_arguments_debug_has_no_secrets() {
+        let args = ProgramArguments::parse_from([
+            "bamboo-bot",
+            "--api-key=api-key-123",
+            "--smtp-password=smtp-pass-456",
+            "--webhook-url=https://hooks.slack.com/services/T000/B000/token789",
+        ]);
+
+        let debug = format!("{args:?}");
+
+        assert!(!debug.contains("api-key-123"));
+        assert!(!debug.contains("smtp-pass-456"));
+        assert!(!debug.contains("token789"));
+    }
+}
@@ -0,0 +1,321 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        StatusCode,
+    },
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use itertools::Itertools;
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+
+use crate::{
+    bambo_processor::BambooProcessor,
+    bamboo_client::BambooClient,
+    config::Config,
+    dashboard,
+    days_calculator::DateRange,
+    notification::{send_notification, Notification},
+};
+
+#[derive(Deserialize, Debug)]
+pub struct RangeQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// Fill button of the status page, submitting the range which was shown
+#[derive(Deserialize, Debug)]
+pub struct FillForm {
+    from: String,
+    to: String,
+    token: String,
+}
+
+pub struct AppState {
+    config: Config,
+    /// Random per process and embedded in the fill form, so other web pages can't post to /fill
+    form_token: String,
+}
+
+/// Serves the dashboard for the configured employee until Ctrl+C
+pub async fn serve(config: Config, listen: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = tokio::net::TcpListener::bind(listen).await?;
+    let address = listener.local_addr()?;
+    if !address.ip().is_loopback() && config.dashboard_password.is_none() {
+        return Err(format!(
+            "Refusing to serve the dashboard on {address} without a password. Use --dashboard-password=<PASSWORD> or env variable DASHBOARD_PASSWORD=<PASSWORD>"
+        )
+        .into());
+    }
+    tracing::info!(url = %format!("http://{address}"), "Dashboard running");
+    axum::serve(listener, router(config, &form_token()?))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+pub fn router(config: Config, form_token: &str) -> Router {
+    let state = Arc::new(AppState { config, form_token: form_token.to_string() });
+    Router::new()
+        .route("/", get(status))
+        .route("/report", get(report))
+        .route("/fill", post(fill))
+        .layer(middleware::from_fn_with_state(state.clone(), require_password))
+        .with_state(state)
+}
+
+fn form_token() -> Result<String, getrandom::Error> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Basic auth with any user name when a dashboard password is configured
+async fn require_password(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let Some(password) = &state.config.dashboard_password else {
+        return next.run(request).await;
+    };
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|credentials| BASE64_STANDARD.decode(credentials).ok())
+        .and_then(|credentials| String::from_utf8(credentials).ok())
+        .is_some_and(|credentials| credentials.split_once(':').is_some_and(|(_, given)| bool::from(given.as_bytes().ct_eq(password.expose().as_bytes()))));
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, "Basic realm=\"Bamboo bot\"")], "Password required").into_response();
+    }
+    next.run(request).await
+}
+
+async fn status(State(state): State<Arc<AppState>>, Query(query): Query<RangeQuery>) -> Response {
+    let range = match DateRange::parse(query.from.as_deref(), query.to.as_deref()) {
+        Ok(range) => range,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e),
+    };
+    let bamboo_client = BambooClient::new(&state.config);
+    let bambo_processor = BambooProcessor::new(&bamboo_client);
+    match bambo_processor.get_timesheet_status(&range).await {
+        Ok(status) => Html(dashboard::render_status(&range, &status, &state.form_token)).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, &e.to_string()),
+    }
+}
+
+async fn report(State(state): State<Arc<AppState>>, Query(query): Query<RangeQuery>) -> Response {
+    let range = match DateRange::parse(query.from.as_deref(), query.to.as_deref()) {
+        Ok(range) => range,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e),
+    };
+    if state.config.team.is_empty() {
+        return Html(dashboard::render_report(&range, &[])).into_response();
+    }
+    let bamboo_client = BambooClient::new(&state.config);
+    let bambo_processor = BambooProcessor::new(&bamboo_client);
+    match bambo_processor.get_team_report(&range, &state.config.team).await {
+        Ok(reports) => Html(dashboard::render_report(&range, &reports)).into_response(),
+        Err(e) => error(StatusCode::BAD_GATEWAY, &e.to_string()),
+    }
+}
+
+/// Fills the range shown on the status page like the daemon does, without asking
+async fn fill(State(state): State<Arc<AppState>>, Form(form): Form<FillForm>) -> Response {
+    if !bool::from(form.token.as_bytes().ct_eq(state.form_token.as_bytes())) {
+        return error(StatusCode::FORBIDDEN, "Invalid form token, reload the page and try again.");
+    }
+    let range = match DateRange::parse(Some(&form.from), Some(&form.to)) {
+        Ok(range) => range,
+        Err(e) => return error(StatusCode::BAD_REQUEST, &e),
+    };
+    let config = &state.config;
+    let bamboo_client = BambooClient::new(config);
+    let bambo_processor = BambooProcessor::new(&bamboo_client);
+    let status = match bambo_processor.get_timesheet_status(&range).await {
+        Ok(status) => status,
+        Err(e) => return error(StatusCode::BAD_GATEWAY, &e.to_string()),
+    };
+    let result = match bambo_processor.submit_entries(&status.entry_plan.entries).await {
+        Ok(result) => result,
+        Err(e) => return error(StatusCode::BAD_GATEWAY, &e.to_string()),
+    };
+    if result.added > 0 {
+        let days = status.entry_plan.entries.iter().map(|entry| String::from(&entry.date)).unique().collect();
+        if let Err(e) = send_notification(config, &Notification::Filled { days, entries: result.added }).await {
+            tracing::warn!(error = %e, "Error while sending notification");
+        }
+    }
+    Html(dashboard::render_fill_result(&result)).into_response()
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Html(dashboard::render_error(message))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    use crate::config::ConfigBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_page() {
+        let bamboo = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&bamboo)
+            .await;
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .build()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(config, "token")).await });
+
+        let response = reqwest::get(format!("http://{address}/?from=2024-08-12&to=2024-08-13")).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        let html = response.text().await.unwrap();
+        assert!(html.contains("<h1>Timesheet 2024-08-12 - 2024-08-13</h1>"));
+        assert!(html.contains("<button type=\"submit\">Fill 2 entries</button>"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_range() {
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .build()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(config, "token")).await });
+
+        let response = reqwest::get(format!("http://{address}/report?from=2024-08-13&to=2024-08-12")).await.unwrap();
+
+        assert_eq!(response.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_report_without_team() {
+        let bamboo = MockServer::start().await;
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .build()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(config, "token")).await });
+
+        let response = reqwest::get(format!("http://{address}/report?from=2024-08-12&to=2024-08-16")).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("No team members."));
+        assert!(bamboo.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fill_submits_the_shown_range() {
+        let bamboo = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&bamboo)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!([])))
+            .mount(&bamboo)
+            .await;
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .bamboo_base_url(bamboo.uri())
+            .lock_file(Some(std::env::temp_dir().join(format!("bamboo-bot-server-{}.lock", std::process::id())).display().to_string()))
+            .build()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(config, "token")).await });
+        let client = reqwest::Client::new();
+
+        let forged = client
+            .post(format!("http://{address}/fill"))
+            .form(&[("from", "2024-08-12"), ("to", "2024-08-13"), ("token", "guess")])
+            .send()
+            .await
+            .unwrap();
+        let response = client
+            .post(format!("http://{address}/fill"))
+            .form(&[("from", "2024-08-12"), ("to", "2024-08-13"), ("token", "token")])
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(forged.status(), 403);
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("Added 2 entries"));
+        let requests = bamboo.received_requests().await.unwrap();
+        let stored: serde_json::Value = requests.iter().find(|request| request.method.as_str() == "POST").unwrap().body_json().unwrap();
+        assert_eq!(stored["entries"][0]["date"], "2024-08-12");
+        assert_eq!(stored["entries"][1]["date"], "2024-08-13");
+    }
+
+    #[tokio::test]
+    async fn test_password_protection() {
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .dashboard_password(Some("open sesame".into()))
+            .build()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(config, "token")).await });
+        let client = reqwest::Client::new();
+
+        let anonymous = client.get(format!("http://{address}/?from=2024-08-13&to=2024-08-12")).send().await.unwrap();
+        let authorized = client
+            .get(format!("http://{address}/?from=2024-08-13&to=2024-08-12"))
+            .basic_auth("anyone", Some("open sesame"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(anonymous.status(), 401);
+        assert_eq!(authorized.status(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_public_address_without_password() {
+        let config = ConfigBuilder::default()
+            .api_key("key".to_string())
+            .employee_id(12)
+            .company("acme".to_string())
+            .build()
+            .unwrap();
+
+        let result = serve(config, "0.0.0.0:0").await;
+
+        assert!(result.unwrap_err().to_string().contains("without a password"));
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    fs::{File, OpenOptions},
+    time::Duration,
+};
+
+use fs2::FileExt;
+
+use crate::config::Config;
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+const WAIT_LIMIT: Duration = Duration::from_secs(60);
+
+/// Exclusive lock on a file held while entries are checked and posted, so concurrent runs can't add the same entries.
+/// The lock is released when dropped or when the process dies.
+pub struct SubmissionLock {
+    file: File,
+}
+
+impl SubmissionLock {
+    /// Waits until other runs release the lock
+    pub async fn acquire(path: &str) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Error while opening lock file {path}: {e}"))?;
+
+        let mut waited = Duration::ZERO;
+        while file.try_lock_exclusive().is_err() {
+            if waited >= WAIT_LIMIT {
+                return Err(format!("Another run is still submitting entries, lock file {path} is held"));
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+            waited += RETRY_INTERVAL;
+        }
+        Ok(SubmissionLock { file })
+    }
+}
+
+impl Drop for SubmissionLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Configured lock file or one per company and employee in the temp directory
+pub fn lock_path(config: &Config) -> String {
+    config.lock_file.clone().unwrap_or_else(|| {
+        std::env::temp_dir()
+            .join(format!("bamboo-bot-{}-{}.lock", config.company, config.employee_id))
+            .to_string_lossy()
+            .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive() {
+        let path = std::env::temp_dir().join(format!("bamboo-bot-test-{}.lock", std::process::id()));
+        let path = path.to_string_lossy();
+
+        let lock = SubmissionLock::acquire(&path).await.unwrap();
+        let other = OpenOptions::new().write(true).open(path.as_ref()).unwrap();
+        assert!(other.try_lock_exclusive().is_err());
+
+        drop(lock);
+        assert!(other.try_lock_exclusive().is_ok());
+        FileExt::unlock(&other).unwrap();
+        std::fs::remove_file(path.as_ref()).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+use crate::model::{
+    get_timeoff_balances::timeoff_balance::TimeOffBalance,
+    get_timeoff_requests::timeoff_request::TimeOffRequest,
+};
+
+/// Balance of a single time off policy today and at the end of the year
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyBalance {
+    pub type_id: String,
+    pub name: String,
+    pub units: String,
+    pub current: f32,
+    pub approved: f32,
+    pub projected: f32,
+}
+
+/// Joins current and projected balances by time off type and sums up approved requests of that type.
+/// Requests in different units than the policy are converted with `daily_hours`.
+pub fn summarize_balances(
+    current: &[TimeOffBalance],
+    projected: &[TimeOffBalance],
+    requests: &[TimeOffRequest],
+    daily_hours: f32,
+) -> Vec<PolicyBalance> {
+    current
+        .iter()
+        .map(|balance| {
+            let approved = requests
+                .iter()
+                .filter(|request| request.r#type.id == balance.time_off_type && request.status.status == "approved")
+                .filter_map(|request| {
+                    let amount = request.amount.amount.parse::<f32>().ok()?;
+                    Some(convert(amount, &request.amount.unit, &balance.units, daily_hours))
+                })
+                .sum();
+            let projected = projected
+                .iter()
+                .find(|projected| projected.time_off_type == balance.time_off_type)
+                .and_then(|projected| projected.balance.parse::<f32>().ok())
+                .unwrap_or_default();
+
+            PolicyBalance {
+                type_id: String::from(&balance.time_off_type),
+                name: String::from(&balance.name),
+                units: String::from(&balance.units),
+                current: balance.balance.parse::<f32>().unwrap_or_default(),
+                approved,
+                projected,
+            }
+        })
+        .collect()
+}
+
+fn convert(amount: f32, from_units: &str, to_units: &str, daily_hours: f32) -> f32 {
+    match (from_units, to_units) {
+        ("days", "hours") => amount * daily_hours,
+        ("hours", "days") => amount / daily_hours,
+        _ => amount,
+    }
+}
+
+pub fn render_balances(balances: &[PolicyBalance], year_end: &str) -> String {
+    let name_width = balances
+        .iter()
+        .map(|balance| balance.name.chars().count())
+        .chain(["Policy".len()])
+        .max()
+        .unwrap_or_default();
+    let projected_header = format!("Projected {year_end}");
+
+    let mut lines = vec![format!(
+        "{:<name_width$}  {:>14}  {:>18}  {:>20}",
+        "Policy", "Balance today", "Approved this year", projected_header
+    )];
+    for balance in balances {
+        lines.push(format!(
+            "{:<name_width$}  {:>14}  {:>18}  {:>20}",
+            balance.name,
+            format!("{:.1} {}", balance.current, balance.units),
+            format!("{:.1} {}", balance.approved, balance.units),
+            format!("{:.1} {}", balance.projected, balance.units),
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn balance(time_off_type: &str, name: &str, units: &str, balance: &str) -> TimeOffBalance {
+        TimeOffBalance {
+            time_off_type: time_off_type.to_string(),
+            name: name.to_string(),
+            units: units.to_string(),
+            balance: balance.to_string(),
+            end: "2024-12-31".to_string(),
+            policy_type: None,
+            used_year_to_date: None,
+        }
+    }
+
+    fn request(type_id: &str, status: &str, unit: &str, amount: &str) -> TimeOffRequest {
+        serde_json::from_value(json!({
+            "id": "1",
+            "employeeId": "12",
+            "name": "Anna Nowak",
+            "status": { "lastChanged": "2024-08-01", "lastChangedByUserId": "1", "status": status },
+            "start": "2024-08-19",
+            "end": "2024-08-20",
+            "created": "2024-08-01",
+            "type": { "id": type_id, "name": "Vacation", "icon": "palm-trees" },
+            "amount": { "unit": unit, "amount": amount },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summarize_balances() {
+        let current = vec![balance("78", "Vacation", "days", "13.5"), balance("80", "Sick", "hours", "40")];
+        let projected = vec![balance("78", "Vacation", "days", "20.5")];
+        let requests = vec![
+            request("78", "approved", "days", "2"),
+            request("78", "approved", "hours", "4"),
+            request("78", "denied", "days", "5"),
+            request("80", "approved", "days", "1"),
+        ];
+
+        let balances = summarize_balances(&current, &projected, &requests, 8.0);
+
+        assert_eq!(balances[0].current, 13.5);
+        assert_eq!(balances[0].approved, 2.5);
+        assert_eq!(balances[0].projected, 20.5);
+        assert_eq!(balances[1].approved, 8.0);
+        assert_eq!(balances[1].projected, 0.0);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    days_calculator::{format_date, parse_date},
+    model::{
+        add_timeoff_request::add_request::{AddTimeOffDate, AddTimeOffNote, AddTimeOffRequest},
+        get_timeoff_requests::request_type::RequestType,
+    },
+};
+
+/// Finds the time off type by ID or case insensitive name
+pub fn find_request_type<'t>(types: &'t [RequestType], value: &str) -> Result<&'t RequestType, String> {
+    types
+        .iter()
+        .find(|request_type| request_type.id == value || request_type.name.eq_ignore_ascii_case(value))
+        .ok_or_else(|| {
+            let known = types
+                .iter()
+                .map(|request_type| format!("{} ({})", request_type.id, request_type.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Unknown time off type {value}, known types: {known}")
+        })
+}
+
+/// Parses "YYYY-MM-DD=AMOUNT", e.g. "2024-08-19=0.5"
+pub fn parse_day_amount(value: &str) -> Result<(String, f32), String> {
+    let invalid = || format!("Invalid day amount {value}, expected e.g. 2024-08-19=0.5");
+    let (day, amount) = value.split_once('=').ok_or_else(invalid)?;
+    let day = parse_date(day.trim())?;
+    let amount = amount.trim().parse::<f32>().map_err(|_| invalid())?;
+    if amount < 0.0 {
+        return Err(invalid());
+    }
+    Ok((format_date(&day), amount))
+}
+
+/// Builds the request with a full day on every given day unless the amount is overridden.
+/// Days with zero amount are left out, amounts are in hours when the type is counted in hours.
+/// Overridden days must be among the given days, so they can't move the start or end of the request.
+pub fn build_request(
+    request_type: &RequestType,
+    days: &[String],
+    day_amounts: &[(String, f32)],
+    note: Option<&str>,
+    daily_hours: f32,
+) -> Result<AddTimeOffRequest, String> {
+    let full_day = if request_type.units.as_deref() == Some("hours") { daily_hours } else { 1.0 };
+    let mut dates: Vec<AddTimeOffDate> = days
+        .iter()
+        .map(|day| AddTimeOffDate { ymd: String::from(day), amount: full_day })
+        .collect();
+    for (day, amount) in day_amounts {
+        match dates.iter_mut().find(|date| &date.ymd == day) {
+            Some(date) => date.amount = *amount,
+            None => return Err(format!("{day} is not a working day of the request, it is outside of the range, a holiday or a day off")),
+        }
+    }
+    dates.retain(|date| date.amount > 0.0);
+    dates.sort_by(|a, b| a.ymd.cmp(&b.ymd));
+
+    let (Some(first), Some(last)) = (dates.first(), dates.last()) else {
+        return Err("No days to request time off for".to_string());
+    };
+    Ok(AddTimeOffRequest {
+        status: "requested".to_string(),
+        start: String::from(&first.ymd),
+        end: String::from(&last.ymd),
+        time_off_type_id: String::from(&request_type.id),
+        amount: dates.iter().map(|date| date.amount).sum(),
+        notes: note
+            .map(|note| vec![AddTimeOffNote { from: "employee".to_string(), note: note.to_string() }])
+            .unwrap_or_default(),
+        dates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_type(id: &str, name: &str, units: &str) -> RequestType {
+        RequestType { id: id.to_string(), name: name.to_string(), icon: "palm-trees".to_string(), units: Some(units.to_string()) }
+    }
+
+    #[test]
+    fn test_find_request_type() {
+        let types = vec![request_type("78", "Vacation", "days"), request_type("80", "Sick", "hours")];
+
+        assert_eq!(find_request_type(&types, "80").unwrap().name, "Sick");
+        assert_eq!(find_request_type(&types, "vacation").unwrap().id, "78");
+        assert_eq!(
+            find_request_type(&types, "Holiday").unwrap_err(),
+            "Unknown time off type Holiday, known types: 78 (Vacation), 80 (Sick)"
+        );
+    }
+
+    #[test]
+    fn test_parse_day_amount() {
+        assert_eq!(parse_day_amount("2024-08-19=0.5").unwrap(), ("2024-08-19".to_string(), 0.5));
+        assert!(parse_day_amount("2024-08-19").is_err());
+        assert!(parse_day_amount("2024-08-19=-1").is_err());
+    }
+
+    #[test]
+    fn test_build_request() {
+        let days = vec!["2024-08-19".to_string(), "2024-08-20".to_string(), "2024-08-21".to_string()];
+        let day_amounts = vec![("2024-08-19".to_string(), 0.0), ("2024-08-21".to_string(), 4.0)];
+
+        let request = build_request(&request_type("80", "Sick", "hours"), &days, &day_amounts, Some("Dentist"), 8.0).unwrap();
+
+        assert_eq!(request.start, "2024-08-20");
+        assert_eq!(request.end, "2024-08-21");
+        assert_eq!(request.amount, 12.0);
+        assert_eq!(request.dates, vec![
+            AddTimeOffDate { ymd: "2024-08-20".to_string(), amount: 8.0 },
+            AddTimeOffDate { ymd: "2024-08-21".to_string(), amount: 4.0 },
+        ]);
+        assert_eq!(request.notes[0].note, "Dentist");
+    }
+
+    #[test]
+    fn test_build_request_rejects_other_days() {
+        let days = vec!["2024-08-16".to_string(), "2024-08-19".to_string()];
+        let vacation = request_type("78", "Vacation", "days");
+
+        // weekend inside the range and a day after it
+        assert!(build_request(&vacation, &days, &[("2024-08-17".to_string(), 0.5)], None, 8.0).is_err());
+        assert!(build_request(&vacation, &days, &[("2024-08-20".to_string(), 0.5)], None, 8.0).is_err());
+    }
+
+    #[test]
+    fn test_build_request_without_days() {
+        assert!(build_request(&request_type("78", "Vacation", "days"), &[], &[], None, 8.0).is_err());
+    }
+}
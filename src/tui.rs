@@ -0,0 +1,190 @@
+use std::io;
+
+use chrono::Datelike;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    model::add_timesheet_entry::add_entry_request::AddTimesheetEntry,
+    month_review::{DayKind, MonthReview, ReviewDay, HOURS_STEP_MINUTES},
+};
+
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+const HELP: &str = "arrows move, space toggle day, +/- change hours by 30 min, enter submit selected days, q quit";
+
+/// Shows the month until the user submits (selected entries returned) or quits (None)
+pub fn review_month(review: &mut MonthReview) -> io::Result<Option<Vec<AddTimesheetEntry>>> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, review);
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, review: &mut MonthReview) -> io::Result<Option<Vec<AddTimesheetEntry>>> {
+    loop {
+        terminal.draw(|frame| draw(frame, review))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => review.move_cursor(-1),
+            KeyCode::Right | KeyCode::Char('l') => review.move_cursor(1),
+            KeyCode::Up | KeyCode::Char('k') => review.move_cursor(-7),
+            KeyCode::Down | KeyCode::Char('j') => review.move_cursor(7),
+            KeyCode::Char(' ') => review.toggle(),
+            KeyCode::Char('+') | KeyCode::Char('=') => review.adjust_hours(HOURS_STEP_MINUTES),
+            KeyCode::Char('-') => review.adjust_hours(-HOURS_STEP_MINUTES),
+            KeyCode::Enter => return Ok(Some(review.selected_entries())),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, review: &MonthReview) {
+    let offset = review
+        .days
+        .first()
+        .map(|day| day.date.weekday().num_days_from_monday() as usize)
+        .unwrap_or_default();
+    let weeks = (offset + review.days.len()).div_ceil(7);
+    let [calendar_area, details_area, help_area] = Layout::vertical([
+        Constraint::Length(weeks as u16 + 3),
+        Constraint::Min(6),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let rows = (0..weeks).map(|week| {
+        Row::new((0..7).map(|weekday| {
+            let index = (week * 7 + weekday).checked_sub(offset);
+            match index.and_then(|index| review.days.get(index).map(|day| (index, day))) {
+                Some((index, day)) => day_cell(day, index == review.cursor),
+                None => Cell::from(""),
+            }
+        }))
+    });
+    let selected_days = review.days.iter().filter(|day| day.selected).count();
+    let selected_minutes: i64 = review.days.iter().filter(|day| day.selected).map(ReviewDay::minutes).sum();
+    let title = format!(
+        " Timesheet {} - {}: {selected_days} days, {} selected ",
+        review.days.first().map(|day| day.date.to_string()).unwrap_or_default(),
+        review.days.last().map(|day| day.date.to_string()).unwrap_or_default(),
+        format_hours(selected_minutes),
+    );
+    let calendar = Table::new(rows, [Constraint::Length(14); 7])
+        .header(Row::new(WEEKDAYS).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::bordered().title(title));
+    frame.render_widget(calendar, calendar_area);
+
+    frame.render_widget(Paragraph::new(details(review)).block(Block::bordered().title(" Day ")), details_area);
+    frame.render_widget(Line::from(HELP).style(Style::default().fg(Color::DarkGray)), help_area);
+}
+
+fn day_cell(day: &ReviewDay, is_cursor: bool) -> Cell<'static> {
+    let mark = match (day.kind, day.selected) {
+        (DayKind::Missing, true) => format!("[x] {}", format_hours(day.minutes())),
+        (DayKind::Missing, false) => "[ ]".to_string(),
+        (kind, _) => short_label(kind).to_string(),
+    };
+    let mut style = Style::default().fg(color(day.kind));
+    if is_cursor {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    Cell::from(format!("{:>2} {mark}", day.date.day())).style(style)
+}
+
+fn details(review: &MonthReview) -> Vec<Line<'static>> {
+    let Some(day) = review.current() else {
+        return Vec::new();
+    };
+    let mut lines = vec![Line::from(format!("{} ({}): {}", day.date, day.date.format("%A"), day.kind.label()))];
+    if day.kind == DayKind::Missing {
+        let entries = day.entries.iter().map(|entry| format!("{}-{}", entry.start, entry.end)).collect::<Vec<_>>();
+        lines.push(Line::from(if entries.is_empty() {
+            "Fully taken by existing entries or time off".to_string()
+        } else {
+            format!("Planned {} ({})", entries.join(", "), format_hours(day.minutes()))
+        }));
+        lines.push(Line::from(if day.selected { "Will be submitted" } else { "Will be skipped" }));
+    }
+    lines
+}
+
+fn short_label(kind: DayKind) -> &'static str {
+    match kind {
+        DayKind::NonWorking => "",
+        DayKind::Holiday => "holiday",
+        DayKind::Vacation => "vacation",
+        DayKind::OutOfOffice => "OOO",
+        DayKind::Filled => "filled",
+        DayKind::Missing => "missing",
+    }
+}
+
+fn color(kind: DayKind) -> Color {
+    match kind {
+        DayKind::NonWorking => Color::DarkGray,
+        DayKind::Holiday => Color::Magenta,
+        DayKind::Vacation => Color::Cyan,
+        DayKind::OutOfOffice => Color::Blue,
+        DayKind::Filled => Color::Green,
+        DayKind::Missing => Color::Yellow,
+    }
+}
+
+fn format_hours(minutes: i64) -> String {
+    format!("{:.1}h", minutes as f32 / 60.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::{
+        bambo_processor::TimesheetStatus, days_calculator::DateRange, entry_planner::plan_entries,
+        work_schedule::WorkSchedule,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_draw_month() {
+        let range = DateRange::parse(Some("2024-08-12"), Some("2024-08-16")).unwrap();
+        let eligible_days = vec!["2024-08-12".to_string(), "2024-08-13".to_string()];
+        let status = TimesheetStatus {
+            bank_holidays: vec!["2024-08-15".to_string()],
+            vacation_days: vec![],
+            out_of_office_days: vec![],
+            entry_plan: plan_entries(12, &eligible_days, &[], &WorkSchedule::default()),
+            eligible_days,
+            timesheet_entries: vec![],
+        };
+        let review = MonthReview::new(&range, &status, &WorkSchedule::default());
+        let mut terminal = Terminal::new(TestBackend::new(110, 14)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &review)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Timesheet 2024-08-12 - 2024-08-16: 2 days, 16.0h selected"));
+        assert!(screen.contains("13 [x] 8.0h"));
+        assert!(screen.contains("15 holiday"));
+        assert!(screen.contains("2024-08-12 (Monday): missing"));
+        assert!(screen.contains("Planned 08:00-16:00 (8.0h)"));
+    }
+}
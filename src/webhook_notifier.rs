@@ -0,0 +1,123 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+use crate::{
+    notification::{format_day, Notification},
+    secret::Secret,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum WebhookFormat {
+    Slack,
+    Teams,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    /// Incoming webhook urls carry their token in the path
+    pub url: Secret,
+    pub format: WebhookFormat,
+}
+
+pub struct WebhookNotifier<'a> {
+    webhook: &'a WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl<'a> WebhookNotifier<'a> {
+    pub fn new(webhook: &'a WebhookConfig) -> Self {
+        WebhookNotifier {
+            webhook,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn send(&self, notification: &Notification) -> Result<(), Box<dyn std::error::Error>> {
+        let response = self
+            .client
+            .post(self.webhook.url.expose())
+            .json(&payload(self.webhook.format, notification))
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        if !response.status().is_success() {
+            return Err(format!("Webhook responded with status {}", response.status()).into());
+        }
+        Ok(())
+    }
+}
+
+/// Slack incoming webhook message or Teams connector MessageCard
+pub fn payload(format: WebhookFormat, notification: &Notification) -> Value {
+    let days: Vec<String> = notification.days().iter().map(|day| format_day(day)).collect();
+    match format {
+        WebhookFormat::Slack => json!({
+            "text": format!(
+                "*{}*\n{}",
+                notification.subject(),
+                days.iter().map(|day| format!("• {day}")).collect::<Vec<_>>().join("\n")
+            ),
+        }),
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": notification.subject(),
+            "title": notification.subject(),
+            "text": days.iter().map(|day| format!("- {day}")).collect::<Vec<_>>().join("\n\n"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{body_partial_json, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_slack_payload() {
+        let notification = Notification::Filled { days: vec!["2024-09-23".to_string()], entries: 1 };
+
+        assert_eq!(
+            payload(WebhookFormat::Slack, &notification),
+            json!({ "text": "*Timesheet: filled 1 days*\n• 2024-09-23 (Monday)" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_teams_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/webhook"))
+            .and(body_partial_json(json!({
+                "@type": "MessageCard",
+                "title": "Timesheet: you are missing 2 days this month",
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let webhook = WebhookConfig { url: format!("{}/webhook", server.uri()).into(), format: WebhookFormat::Teams };
+        let notification = Notification::MissingDays { days: vec!["2024-09-23".to_string(), "2024-09-24".to_string()] };
+
+        WebhookNotifier::new(&webhook).send(&notification).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_on_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let webhook = WebhookConfig { url: server.uri().into(), format: WebhookFormat::Slack };
+
+        let result = WebhookNotifier::new(&webhook).send(&Notification::MissingDays { days: vec![] }).await;
+
+        assert!(result.is_err());
+    }
+}
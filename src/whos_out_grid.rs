@@ -0,0 +1,141 @@
+use chrono::NaiveDate;
+use itertools::Itertools;
+
+use crate::{days_calculator::DateRange, model::get_whos_out::time_off_entry::TimeOffEntry, work_schedule::WorkSchedule};
+
+const OUT: &str = "X";
+const HOLIDAY: &str = "H";
+const WEEKEND: &str = ".";
+const IN: &str = "-";
+
+/// Renders a calendar grid with a row for every person who is out in the range and a column for every day.
+/// Holidays get their own row, the last row counts people out on each working day of the schedule.
+pub fn render_whos_out(range: &DateRange, entries: &[TimeOffEntry], schedule: &WorkSchedule) -> String {
+    let days: Vec<NaiveDate> = range.days().collect();
+    let people: Vec<(i32, &str)> = entries
+        .iter()
+        .filter_map(|entry| entry.employee_id.map(|id| (id, entry.name.as_str())))
+        .unique_by(|(id, _)| *id)
+        .sorted_by_key(|(_, name)| name.to_string())
+        .collect();
+    let holidays: Vec<&TimeOffEntry> = entries.iter().filter(|entry| entry.employee_id.is_none()).collect();
+
+    let name_width = people
+        .iter()
+        .map(|(_, name)| name.chars().count())
+        .chain(["Holidays".len(), "Out".len()])
+        .max()
+        .unwrap_or_default();
+    let row = |label: &str, cells: Vec<String>| {
+        format!("{label:<name_width$} {}", cells.iter().map(|cell| format!("{cell:>2}")).join(" "))
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        row("", days.iter().map(|day| day.format("%d").to_string()).collect()),
+        row("", days.iter().map(|day| day.format("%a").to_string()[..2].to_string()).collect()),
+    ];
+    let mut out_per_day = vec![0; days.len()];
+    for (employee_id, name) in &people {
+        let cells = days
+            .iter()
+            .enumerate()
+            .map(|(i, day)| {
+                let out = entries
+                    .iter()
+                    .any(|entry| entry.employee_id == Some(*employee_id) && covers(entry, day));
+                if out {
+                    out_per_day[i] += 1;
+                }
+                cell(day, out, OUT, schedule).to_string()
+            })
+            .collect();
+        lines.push(row(name, cells));
+    }
+    if !holidays.is_empty() {
+        let cells = days
+            .iter()
+            .map(|day| cell(day, holidays.iter().any(|holiday| covers(holiday, day)), HOLIDAY, schedule).to_string())
+            .collect();
+        lines.push(row("Holidays", cells));
+    }
+    let out_cells = days
+        .iter()
+        .zip(&out_per_day)
+        .map(|(day, count)| if schedule.is_working_day(day) { count.to_string() } else { WEEKEND.to_string() })
+        .collect();
+    lines.push(row("Out", out_cells));
+
+    lines.join("\n")
+}
+
+fn cell(day: &NaiveDate, marked: bool, mark: &'static str, schedule: &WorkSchedule) -> &'static str {
+    if marked {
+        mark
+    } else if !schedule.is_working_day(day) {
+        WEEKEND
+    } else {
+        IN
+    }
+}
+
+fn covers(entry: &TimeOffEntry, day: &NaiveDate) -> bool {
+    let start = NaiveDate::parse_from_str(&entry.start, "%Y-%m-%d");
+    let end = NaiveDate::parse_from_str(&entry.end, "%Y-%m-%d");
+    match (start, end) {
+        (Ok(start), Ok(end)) => (start..=end).contains(day),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(employee_id: Option<i32>, name: &str, start: &str, end: &str) -> TimeOffEntry {
+        TimeOffEntry {
+            id: 1,
+            r#type: if employee_id.is_some() { "timeOff" } else { "holiday" }.to_string(),
+            employee_id,
+            name: name.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_whos_out() {
+        let range = DateRange::parse(Some("2024-08-14"), Some("2024-08-19")).unwrap();
+        let entries = vec![
+            entry(Some(2), "Jan Kowalski", "2024-08-16", "2024-08-19"),
+            entry(Some(1), "Anna Nowak", "2024-08-14", "2024-08-14"),
+            entry(None, "Assumption Day", "2024-08-15", "2024-08-15"),
+        ];
+
+        let grid = render_whos_out(&range, &entries, &WorkSchedule::default());
+
+        assert_eq!(
+            grid,
+            [
+                "             14 15 16 17 18 19",
+                "             We Th Fr Sa Su Mo",
+                "Anna Nowak    X  -  -  .  .  -",
+                "Jan Kowalski  -  -  X  X  X  X",
+                "Holidays      -  H  -  .  .  -",
+                "Out           1  0  1  .  .  1",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_whos_out_with_part_time_schedule() {
+        let range = DateRange::parse(Some("2024-08-15"), Some("2024-08-16")).unwrap();
+        let entries = vec![entry(Some(1), "Anna Nowak", "2024-08-15", "2024-08-16")];
+
+        let grid = render_whos_out(&range, &entries, &WorkSchedule::parse("mon-thu", 0.8, "08:00").unwrap());
+
+        assert!(grid.ends_with("Out         1  ."), "{grid}");
+    }
+}
@@ -0,0 +1,157 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeDelta, Weekday};
+
+pub const FULL_TIME_WEEKLY_HOURS: f32 = 40.0;
+
+/// Week 0 of alternating schedules, the Monday before ISO week 1 of 2026. Weeks are counted from here
+/// instead of taking ISO week numbers, which would repeat a pattern after years with 53 weeks.
+const CYCLE_START: (i32, u32, u32) = (2025, 12, 22);
+
+/// Which days and hours the employee works
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkSchedule {
+    /// Working weekdays for every week of the cycle. Pattern `i` applies to weeks where
+    /// `weeks since CYCLE_START % cycle length == i`, in 2026 that is the ISO week number.
+    pub weeks: Vec<Vec<Weekday>>,
+    pub fte: f32,
+    pub start: NaiveTime,
+}
+
+impl Default for WorkSchedule {
+    fn default() -> Self {
+        WorkSchedule {
+            weeks: vec![vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]],
+            fte: 1.0,
+            start: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl WorkSchedule {
+    /// Parses working days like "mon-fri", "mon,tue,wed,thu" or alternating weeks "mon-fri;mon-thu",
+    /// FTE like 0.8 and start of the working day like "08:00".
+    pub fn parse(working_days: &str, fte: f32, start: &str) -> Result<Self, String> {
+        if !(fte > 0.0 && fte <= 1.0) {
+            return Err(format!("FTE should be greater than 0 and at most 1, got {fte}"));
+        }
+        let start = NaiveTime::parse_from_str(start, "%H:%M")
+            .map_err(|e| format!("Invalid start of working day {start}, expected HH:MM: {e}"))?;
+
+        let mut weeks = Vec::new();
+        for week in working_days.split(';') {
+            let mut days = Vec::new();
+            for part in week.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+                match part.split_once('-') {
+                    Some((from, to)) => {
+                        let (from, to) = (parse_weekday(from)?, parse_weekday(to)?);
+                        let mut day = from;
+                        loop {
+                            days.push(day);
+                            if day == to {
+                                break;
+                            }
+                            day = day.succ();
+                        }
+                    }
+                    None => days.push(parse_weekday(part)?),
+                }
+            }
+            if days.is_empty() {
+                return Err(format!("No working days in {working_days}"));
+            }
+            weeks.push(days);
+        }
+
+        Ok(WorkSchedule { weeks, fte, start })
+    }
+
+    pub fn is_working_day(&self, day: &NaiveDate) -> bool {
+        let (year, month, day_of_month) = CYCLE_START;
+        let cycle_start = NaiveDate::from_ymd_opt(year, month, day_of_month).unwrap();
+        let weeks = day.week(Weekday::Mon).first_day().signed_duration_since(cycle_start).num_weeks();
+        let week = weeks.rem_euclid(self.weeks.len() as i64) as usize;
+        self.weeks[week].contains(&day.weekday())
+    }
+
+    /// Weekly hours given by FTE spread over average number of working days in a week
+    pub fn daily_hours(&self) -> f32 {
+        let days: usize = self.weeks.iter().map(Vec::len).sum();
+        let average_days = days as f32 / self.weeks.len() as f32;
+        FULL_TIME_WEEKLY_HOURS * self.fte / average_days
+    }
+
+    pub fn working_hours(&self) -> (NaiveTime, NaiveTime) {
+        let minutes = (self.daily_hours() * 60.0).round() as i64;
+        let end = self.start + TimeDelta::minutes(minutes);
+        // don't wrap past midnight
+        (self.start, end.max(self.start))
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| format!("Invalid weekday {value}, expected e.g. mon, tue"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_schedule() {
+        let schedule = WorkSchedule::parse("mon-fri", 1.0, "08:00").unwrap();
+
+        assert_eq!(schedule, WorkSchedule::default());
+        assert_eq!(schedule.working_hours().1, NaiveTime::from_hms_opt(16, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_part_time_schedule() {
+        let four_days = WorkSchedule::parse("mon,tue,wed,thu", 0.8, "09:00").unwrap();
+        let five_days = WorkSchedule::parse("mon-fri", 0.8, "09:00").unwrap();
+        let friday = NaiveDate::from_ymd_opt(2024, 8, 2).unwrap();
+
+        assert!(!four_days.is_working_day(&friday));
+        assert_eq!(four_days.working_hours().1, NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+        assert_eq!(five_days.working_hours().1, NaiveTime::from_hms_opt(15, 24, 0).unwrap());
+    }
+
+    #[test]
+    fn test_alternating_weeks() {
+        let schedule = WorkSchedule::parse("mon-fri;mon-thu", 0.9, "08:00").unwrap();
+        // 2026-01-02 is friday of week 1 after CYCLE_START, 2026-01-09 of week 2
+        let second_pattern_friday = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        let first_pattern_friday = NaiveDate::from_ymd_opt(2026, 1, 9).unwrap();
+
+        assert!(!schedule.is_working_day(&second_pattern_friday));
+        assert!(schedule.is_working_day(&first_pattern_friday));
+        assert_eq!(schedule.daily_hours(), 8.0);
+    }
+
+    #[test]
+    fn test_alternating_weeks_across_53_week_year() {
+        let schedule = WorkSchedule::parse("mon-fri;mon-thu", 0.9, "08:00").unwrap();
+        let fridays: Vec<bool> = ["2026-12-25", "2027-01-01", "2027-01-08"]
+            .iter()
+            .map(|day| schedule.is_working_day(&NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()))
+            .collect();
+        let working_days = NaiveDate::from_ymd_opt(2026, 12, 28)
+            .unwrap()
+            .iter_days()
+            .take(14)
+            .filter(|day| schedule.is_working_day(day))
+            .count();
+
+        // ISO weeks 52 and 53 of 2026 and week 1 of 2027 still alternate
+        assert_eq!(fridays, vec![true, false, true]);
+        assert_eq!(working_days, 9);
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        assert!(WorkSchedule::parse("mon-fry", 1.0, "08:00").is_err());
+        assert!(WorkSchedule::parse("mon-fri", 1.5, "08:00").is_err());
+        assert!(WorkSchedule::parse("mon-fri", 1.0, "8am").is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use std::{fs, path::PathBuf};
+
+use serde_json::{json, Value};
+use wiremock::{
+    matchers::{method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// Runs the bot with a clean environment and returns its JSON output
+async fn run_bot(args: &[&str]) -> Value {
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_bamboo-bot"))
+        .env_clear()
+        .current_dir(std::env::temp_dir())
+        .args(["--api-key=secret-key", "--company=acme", "--employee-id=12", "--output=json"])
+        .args(args)
+        .output()
+        .await
+        .unwrap();
+    assert!(output.status.success(), "bot failed: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Serves every fixture on its BambooHR path for company `acme`, matching the query when an endpoint has several
+async fn mock_bamboo() -> MockServer {
+    let server = MockServer::start().await;
+    for file in fs::read_dir(FIXTURES).unwrap() {
+        let fixtures: Vec<Value> = serde_json::from_str(&fs::read_to_string(file.unwrap().path()).unwrap()).unwrap();
+        for fixture in &fixtures {
+            let mut mock = Mock::given(method(fixture["method"].as_str().unwrap()))
+                .and(path(format!("/api/gateway.php/acme/v1/{}", fixture["path"].as_str().unwrap())));
+            if let (true, Some(query)) = (fixtures.len() > 1, fixture["query"].as_str()) {
+                for (name, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+                    mock = mock.and(query_param(name, value));
+                }
+            }
+            mock.respond_with(ResponseTemplate::new(fixture["status"].as_u64().unwrap() as u16).set_body_json(&fixture["body"]))
+                .mount(&server)
+                .await;
+        }
+    }
+    server
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bamboo-bot-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn expected_team_report() -> Value {
+    json!([
+        { "employeeId": 12, "name": "Person 1", "missingDays": ["2024-08-13", "2024-08-14"] },
+        { "employeeId": 13, "name": "Person 2", "missingDays": [] },
+    ])
+}
+
+fn sorted_missing_days(mut report: Value) -> Value {
+    for employee in report.as_array_mut().unwrap() {
+        employee["missingDays"].as_array_mut().unwrap().sort_by_key(|day| day.to_string());
+    }
+    report
+}
+
+#[tokio::test]
+async fn test_team_report_replayed_from_fixtures() {
+    let replay = format!("--replay-fixtures={FIXTURES}");
+
+    let report = run_bot(&[&replay, "team-report", "--from=2024-08-12", "--to=2024-08-16", "--team=12,13"]).await;
+
+    assert_eq!(sorted_missing_days(report), expected_team_report());
+}
+
+#[tokio::test]
+async fn test_team_report_against_mock_server() {
+    let bamboo = mock_bamboo().await;
+    let base_url = format!("--bamboo-base-url={}", bamboo.uri());
+
+    let report = run_bot(&[&base_url, "team-report", "--from=2024-08-12", "--to=2024-08-16", "--team=12,13"]).await;
+
+    assert_eq!(sorted_missing_days(report), expected_team_report());
+    let requests = bamboo.received_requests().await.unwrap();
+    let entries_request = requests.iter().find(|request| request.url.path().ends_with("/timesheet_entries")).unwrap();
+    assert_eq!(entries_request.url.query(), Some("start=2024-08-12&end=2024-08-16&employeeIds=12%2C13"));
+    assert!(requests.iter().all(|request| request.headers["authorization"] == "Basic c2VjcmV0LWtleTo="));
+}
+
+#[tokio::test]
+async fn test_balance_replayed_from_fixtures() {
+    let replay = format!("--replay-fixtures={FIXTURES}");
+
+    let balances = run_bot(&[&replay, "balance"]).await;
+
+    assert_eq!(balances[0]["name"], "Vacation");
+    assert_eq!(balances[0]["current"], 13.5);
+    assert_eq!(balances[0]["projected"], 20.5);
+}
+
+#[tokio::test]
+async fn test_recorded_fixtures_are_anonymized_and_replayable() {
+    let bamboo = MockServer::start().await;
+    Mock::given(path("/api/gateway.php/acme/v1/time_off/whos_out"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            { "id": 1, "type": "timeOff", "employeeId": 13, "name": "Anna Nowak", "start": "2024-08-13", "end": "2024-08-14" },
+        ])))
+        .mount(&bamboo)
+        .await;
+    let dir = temp_dir("record");
+    let base_url = format!("--bamboo-base-url={}", bamboo.uri());
+    let record = format!("--record-fixtures={}", dir.display());
+    let replay = format!("--replay-fixtures={}", dir.display());
+
+    let recorded = run_bot(&[&base_url, &record, "whos-out", "--from=2024-08-12", "--to=2024-08-16"]).await;
+    let replayed = run_bot(&[&replay, "whos-out", "--from=2024-08-12", "--to=2024-08-16"]).await;
+
+    let fixture = fs::read_to_string(dir.join("get_time_off_whos_out.json")).unwrap();
+    assert!(!fixture.contains("Anna"));
+    assert!(!fixture.contains("secret-key"));
+    assert_eq!(recorded[0]["name"], "Anna Nowak");
+    assert_eq!(replayed[0]["name"], "Person 1");
+    assert_eq!(replayed[0]["start"], recorded[0]["start"]);
+    fs::remove_dir_all(dir).unwrap();
+}
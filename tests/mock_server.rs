@@ -0,0 +1,82 @@
+use std::process::Stdio;
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    process::{Child, Command},
+};
+
+const SEED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/mock_seed.json");
+
+/// Starts bamboo-mock on a free port and returns it with its base url
+async fn start_mock() -> (Child, String) {
+    let mut mock = Command::new(env!("CARGO_BIN_EXE_bamboo-mock"))
+        .args([&format!("--seed={SEED}"), "--listen=127.0.0.1:0"])
+        .stdout(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(mock.stdout.take().unwrap()).read_line(&mut line).await.unwrap();
+    let base_url = line.trim().rsplit(' ').next().unwrap().to_string();
+    (mock, base_url)
+}
+
+async fn run_bot(base_url: &str, employee_id: i32, args: &[&str]) -> Value {
+    let lock_file = std::env::temp_dir().join(format!("bamboo-bot-mock-{}.lock", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_bamboo-bot"))
+        .env_clear()
+        .current_dir(std::env::temp_dir())
+        .args(["--api-key=key", "--company=acme", "--output=json"])
+        .args([
+            format!("--employee-id={employee_id}"),
+            format!("--bamboo-base-url={base_url}"),
+            format!("--lock-file={}", lock_file.display()),
+        ])
+        .args(args)
+        .output()
+        .await
+        .unwrap();
+    assert!(output.status.success(), "bot failed: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[tokio::test]
+async fn test_fill_twice_against_mock() {
+    let (_mock, base_url) = start_mock().await;
+
+    let fill = ["fill", "--from=2024-08-12", "--to=2024-08-16", "--yes"];
+    let first = run_bot(&base_url, 12, &fill).await;
+    let second = run_bot(&base_url, 12, &fill).await;
+
+    // Assumption Day on 2024-08-15 is left out
+    assert_eq!(first["plannedEntries"].as_array().unwrap().len(), 4);
+    assert_eq!(first["submitted"]["added"], 4);
+    assert_eq!(second["plannedEntries"], serde_json::json!([]));
+    assert_eq!(second["submitted"]["added"], 0);
+}
+
+#[tokio::test]
+async fn test_team_report_against_mock() {
+    let (_mock, base_url) = start_mock().await;
+
+    let report = run_bot(&base_url, 14, &["team-report", "--from=2024-08-12", "--to=2024-08-16", "--direct-reports"]).await;
+
+    assert_eq!(report[0]["name"], "Person 1");
+    assert_eq!(report[1]["name"], "Person 2");
+    assert_eq!(report[1]["missingDays"], serde_json::json!(["2024-08-16"]));
+}
+
+#[tokio::test]
+async fn test_request_timeoff_against_mock() {
+    let (_mock, base_url) = start_mock().await;
+
+    let requested = run_bot(&base_url, 12, &["request-timeoff", "--type=vacation", "--from=2024-08-19", "--to=2024-08-20", "--yes"]).await;
+    let balances = run_bot(&base_url, 12, &["balance"]).await;
+
+    assert_eq!(requested["request"]["timeOffTypeId"], "78");
+    assert_eq!(requested["request"]["amount"], 2.0);
+    assert_eq!(requested["response"]["status"]["status"], "requested");
+    assert_eq!(balances[0]["name"], "Vacation");
+    assert_eq!(balances[0]["current"], 20.0);
+}